@@ -0,0 +1,80 @@
+use scrypto::prelude::*;
+
+/// The number of trades a player has to execute within one competition to earn the
+/// `HundredTrades` achievement.
+pub const HUNDRED_TRADES_MILESTONE: u64 = 100;
+
+/// The multiple of the starting balance a portfolio has to reach to earn the `TenXReturn`
+/// achievement.
+pub const TEN_X_RETURN_MULTIPLE: Decimal = dec!(10);
+
+/// The milestones a player can reach during a competition.
+///
+/// Badges are only awarded by `Competition`, which sees every trade of its players: the
+/// `TradeSimulator` is shared by all competitions, prices trades without knowing the user and
+/// cannot mint the badges of a single competition.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Achievement {
+    /// Executed the first trade of a competition.
+    FirstTrade,
+    /// Grew the portfolio to ten times the starting balance.
+    TenXReturn,
+    /// Finished a competition in one of the top three places.
+    TopThreeFinish,
+    /// Executed a hundred trades within a single competition.
+    HundredTrades,
+    /// Reached the end of a competition without being liquidated.
+    SurvivedCompetition,
+}
+
+impl Achievement {
+    /// Returns the badge configuration used when the admin did not configure one.
+    pub fn default_config(&self) -> AchievementConfig {
+        let (name, description) = match self {
+            Achievement::FirstTrade => {
+                ("First Trade", "Executed the first trade of a competition.")
+            }
+            Achievement::TenXReturn => (
+                "10x Return",
+                "Grew the portfolio to ten times its starting value.",
+            ),
+            Achievement::TopThreeFinish => {
+                ("Podium Finish", "Finished a competition in the top three.")
+            }
+            Achievement::HundredTrades => (
+                "Centurion",
+                "Executed a hundred trades in a single competition.",
+            ),
+            Achievement::SurvivedCompetition => (
+                "Survivor",
+                "Made it through a competition without being liquidated.",
+            ),
+        };
+
+        AchievementConfig {
+            name: name.to_string(),
+            description: description.to_string(),
+            key_image_url: "https://colosseum.com/images/LogoColosseum.png".to_string(),
+        }
+    }
+}
+
+/// The display data of an achievement badge, configurable per competition.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct AchievementConfig {
+    pub name: String,
+    pub description: String,
+    pub key_image_url: String,
+}
+
+/// The non-fungible data of a minted achievement badge.
+#[derive(ScryptoSbor, NonFungibleData)]
+pub struct AchievementBadge {
+    pub name: String,
+    pub description: String,
+    pub key_image_url: Url,
+    pub achievement: Achievement,
+    pub user_id: String,
+    pub competition: ComponentAddress,
+    pub awarded_at: Instant,
+}
@@ -0,0 +1,37 @@
+use scrypto::prelude::*;
+
+/// The outcome of the spectator bets of a settled competition.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct BettingResult {
+    /// The winner of the competition.
+    pub winner_id: String,
+    /// The XRD staked on the winner.
+    pub winning_pool: Decimal,
+    /// The XRD staked on players who were still in the competition at settlement.
+    pub payout_pool: Decimal,
+}
+
+impl BettingResult {
+    /// Calculates the XRD a bettor receives for their stake on the winner. All stakes are paid
+    /// back if no one bet on the winner.
+    ///
+    /// # Arguments
+    ///
+    /// * `winning_stake` - The bettor's stake on the winner.
+    /// * `total_stake` - The bettor's stakes on all players still in the competition.
+    ///
+    /// # Returns
+    ///
+    /// The pro-rata share of the payout pool.
+    pub fn payout(&self, winning_stake: Decimal, total_stake: Decimal) -> Decimal {
+        if self.winning_pool.is_zero() {
+            return total_stake;
+        }
+
+        (winning_stake * self.payout_pool)
+            .checked_div(self.winning_pool)
+            .expect("Division by zero error")
+            .checked_round(DIVISIBILITY_MAXIMUM, RoundingMode::ToZero)
+            .expect("Rounding error")
+    }
+}
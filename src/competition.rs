@@ -1,15 +1,236 @@
+#![allow(clippy::too_many_arguments)]
+
+use crate::achievement::{
+    Achievement, AchievementBadge, AchievementConfig, HUNDRED_TRADES_MILESTONE,
+    TEN_X_RETURN_MULTIPLE,
+};
+use crate::betting::BettingResult;
+use crate::copy_trading::{
+    CopySettings, CopyTrade, Follow, IMMEDIATE_MIRROR_LIMIT, MAX_MIRRORED_TRADES_PER_CALL,
+    MAX_PROFIT_SHARE,
+};
+use crate::events::{
+    AchievementAwardedEvent, BetPlacedEvent, BetWinningsClaimedEvent, CompetitionCancelledEvent,
+    CompetitionSettledEvent, CompetitionTimeChangedEvent, EntryFeeRefundedEvent,
+    KeeperRewardPaidEvent, KeeperRewardsFundedEvent, LeaderFollowedEvent, LeaderUnfollowedEvent,
+    OptionBoughtEvent, OptionSettledEvent, OrderScheduledEvent, PerpetualPositionClosedEvent,
+    PerpetualPositionOpenedEvent, PrizeClaimedEvent, PrizePoolSponsoredEvent, ProfitSharePaidEvent,
+    ReferralFeesClaimedEvent, ReferralRecordedEvent, ScheduledOrderCancelledEvent,
+    ScheduledOrderExecutedEvent, SnapshotTakenEvent, SponsorAddedEvent, TradeCommittedEvent,
+    TradeExecutedEvent, TradeMirroredEvent, UserRegisteredEvent, UserUnregisteredEvent,
+    VestedPrizeWithdrawnEvent, VirtualAssetCreatedEvent,
+};
+use crate::keeper::{KeeperAction, KeeperRewardConfig};
+use crate::options::{OptionContract, OptionKind, OptionPositionValue, MAX_OPEN_OPTIONS};
+use crate::oracle::simple_oracle::SimpleOracle;
+use crate::perpetuals::{
+    PerpetualMarket, PerpetualMarketConfig, PerpetualPosition, PerpetualPositionValue, PositionSide,
+};
+use crate::referral::{ReferralConfig, ReferrerStats};
+use crate::risk::{trade_commitment, RiskRules, TradeProtection, SECONDS_PER_TRADING_DAY};
+use crate::scheduled_orders::{ScheduledOrder, MAX_OPEN_ORDERS};
+use crate::scoring::ScoringRule;
+use crate::season::season::Season;
+use crate::season::SeasonBadge;
+use crate::sponsorship::{Sponsor, SponsorInfo, MAX_SPONSORED_RESOURCES};
 use crate::trade_simulator::trade_simulator::TradeSimulator;
 use crate::user_asset_vault::user_asset_vault::UserAssetVault;
+use crate::user_asset_vault::Portfolio;
+use crate::vesting::{PrizeClaim, VestingSchedule};
 
 use scrypto::prelude::*;
+use std::cmp::Reverse;
+
+/// The snapshot interval used until the admin sets one, in seconds.
+const DEFAULT_SNAPSHOT_INTERVAL: i64 = 60 * 60;
+
+/// The amount of FUSD a player receives when registering on time.
+const STARTING_BALANCE: Decimal = dec!(10000);
+
+/// The number of seconds after the competition end during which it can be settled, so the
+/// final ranking is based on the prices at the end.
+const SETTLEMENT_WINDOW: i64 = 60 * 60;
 
 #[derive(ScryptoSbor, ManifestSbor)]
 struct CompetitionData {
+    registration_start: Instant,
+    registration_end: Instant,
     competition_start: Instant,
     competition_end: Instant,
 }
 
+impl CompetitionData {
+    /// Asserts that the registration window and the competition times are in order.
+    fn assert_valid(&self) {
+        assert!(
+            self.registration_start < self.registration_end,
+            "Registration must end after it starts."
+        );
+        assert!(
+            self.competition_start < self.competition_end,
+            "The competition must end after it starts."
+        );
+        assert!(
+            self.registration_start <= self.competition_start,
+            "Registration must open before the competition starts."
+        );
+        assert!(
+            self.registration_end <= self.competition_end,
+            "Registration must close before the competition ends."
+        );
+    }
+}
+
+/// The lifecycle of a competition.
+///
+/// The statuses up to `Ended` follow from the competition times, `Settled` and `Cancelled` are
+/// reached by calling `settle` and `cancel`.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompetitionStatus {
+    /// The competition exists but registration has not opened yet.
+    Created,
+    /// Registration has opened and the competition has not started yet.
+    RegistrationOpen,
+    /// Players are trading.
+    Running,
+    /// Trading is over and the competition waits for settlement.
+    Ended,
+    /// The final ranking is fixed and the prizes can be claimed.
+    Settled,
+    /// The competition was called off and the entry fees can be refunded.
+    Cancelled,
+}
+
+impl CompetitionStatus {
+    /// Checks whether a competition with this status can move to `next`.
+    ///
+    /// Settled and cancelled competitions never change their status again, only ended competitions
+    /// can be settled and all other moves go forward in time.
+    pub fn can_transition_to(&self, next: CompetitionStatus) -> bool {
+        match (self, next) {
+            (CompetitionStatus::Settled | CompetitionStatus::Cancelled, _) => false,
+            (_, CompetitionStatus::Cancelled) => true,
+            (_, CompetitionStatus::Settled) => *self == CompetitionStatus::Ended,
+            _ => next > *self,
+        }
+    }
+
+    /// Checks whether this status is final.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            CompetitionStatus::Settled | CompetitionStatus::Cancelled
+        )
+    }
+}
+
+/// The starting balance of players who register after the competition started.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LateRegistrationPolicy {
+    /// Late players receive the full starting balance.
+    FullBalance,
+    /// Late players receive the share of the starting balance matching the remaining time.
+    ProRated,
+    /// Late players receive the starting balance reduced by a fraction, e.g. 0.1 for 10%.
+    Penalized(Decimal),
+}
+
+/// Who is allowed to register for a competition.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistrationAccess {
+    /// Any holder of a user token can register.
+    Public,
+    /// Only users on the allowlist can register.
+    Allowlist,
+    /// Only holders of the given badge, such as a community NFT, can register.
+    Badge(ResourceAddress),
+    /// Only users with an unused invite code can register.
+    InviteCode,
+}
+
+/// The FUSD value of a user's portfolio at the time of a snapshot, without referral bonuses.
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EquityPoint {
+    pub timestamp: Instant,
+    pub value: Decimal,
+}
+
+/// A player's position on the leaderboard.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    pub user_id: String,
+    pub portfolio_value: Decimal, // The FUSD value without referral bonuses.
+    pub score: Decimal,
+}
+
+/// A single trade as stored in a user's trade history.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct TradeRecord {
+    pub timestamp: Instant,
+    pub from_address: ResourceAddress,
+    pub to_address: ResourceAddress,
+    pub from_amount: Decimal,
+    pub to_amount: Decimal,
+    pub from_price: Decimal,
+    pub to_price: Decimal,
+    pub fee: Decimal,
+}
+
+#[derive(ScryptoSbor, Default)]
+struct UserStats {
+    starting_balance: Decimal,
+    entry_fee_paid: Decimal,
+    trade_count: u64,
+    achievements: Vec<Achievement>,
+    trading_day: i64, // The trading day of the latest trade.
+    trades_on_trading_day: u64,
+    last_trade: Option<Instant>,
+    perpetual_assets: Vec<ResourceAddress>, // The assets of the user's open perpetual positions.
+    liquidated: bool,
+    option_ids: Vec<NonFungibleLocalId>, // The user's options that have not been settled yet.
+    order_ids: Vec<u64>,                 // The user's open scheduled orders.
+    copy_trade_count: u64,               // The number of the user's trades published to followers.
+    profit_share: Decimal, // The share of followers' prizes the user asks for as a leader.
+    copied_leaders: Vec<(String, Decimal, Decimal)>, // The leaders whose trades were mirrored, with the agreed profit share and the FUSD value mirrored.
+    referral_fee: Decimal, // The share of the entry fee owed to the user's referrer.
+    referral_bonus: Decimal, // The FUSD received as referral bonuses, which does not count towards the score.
+}
+
 #[blueprint]
+#[events(
+    UserRegisteredEvent,
+    TradeExecutedEvent,
+    TradeCommittedEvent,
+    PerpetualPositionOpenedEvent,
+    PerpetualPositionClosedEvent,
+    OptionBoughtEvent,
+    OptionSettledEvent,
+    OrderScheduledEvent,
+    ScheduledOrderExecutedEvent,
+    ScheduledOrderCancelledEvent,
+    KeeperRewardsFundedEvent,
+    KeeperRewardPaidEvent,
+    LeaderFollowedEvent,
+    LeaderUnfollowedEvent,
+    TradeMirroredEvent,
+    ProfitSharePaidEvent,
+    BetPlacedEvent,
+    BetWinningsClaimedEvent,
+    ReferralRecordedEvent,
+    ReferralFeesClaimedEvent,
+    SponsorAddedEvent,
+    PrizePoolSponsoredEvent,
+    CompetitionTimeChangedEvent,
+    AchievementAwardedEvent,
+    SnapshotTakenEvent,
+    CompetitionSettledEvent,
+    CompetitionCancelledEvent,
+    PrizeClaimedEvent,
+    VestedPrizeWithdrawnEvent,
+    EntryFeeRefundedEvent,
+    UserUnregisteredEvent,
+    VirtualAssetCreatedEvent
+)]
 mod competition {
 
     enable_method_auth! {
@@ -19,22 +240,198 @@ mod competition {
         methods {
             // Methods with public access
             register => PUBLIC;
+            register_with_badge => PUBLIC;
+            register_with_invite_code => PUBLIC;
+            unregister => PUBLIC;
             trade => PUBLIC;
+            commit_trade => PUBLIC;
+            reveal_trade => PUBLIC;
+            open_position => PUBLIC;
+            close_position => PUBLIC;
+            liquidate_position => PUBLIC;
+            apply_funding => PUBLIC;
+            get_perpetual_market => PUBLIC;
+            get_perpetual_positions => PUBLIC;
+            buy_option => PUBLIC;
+            settle_option => PUBLIC;
+            quote_option => PUBLIC;
+            get_option_address => PUBLIC;
+            get_option_volatility => PUBLIC;
+            get_option_positions => PUBLIC;
+            schedule_dca_order => PUBLIC;
+            schedule_twap_order => PUBLIC;
+            cancel_scheduled_order => PUBLIC;
+            execute_scheduled_order => PUBLIC;
+            get_scheduled_order => PUBLIC;
+            get_scheduled_orders => PUBLIC;
+            fund_keeper_rewards => PUBLIC;
+            get_keeper_reward_config => PUBLIC;
+            get_keeper_reward_balance => PUBLIC;
+            follow => PUBLIC;
+            unfollow => PUBLIC;
+            set_profit_share => PUBLIC;
+            mirror_trades => PUBLIC;
+            get_follow => PUBLIC;
+            get_followers => PUBLIC;
+            get_profit_share => PUBLIC;
+            place_bet => PUBLIC;
+            claim_bet_winnings => PUBLIC;
+            get_betting_close_time => PUBLIC;
+            get_bet_pools => PUBLIC;
+            get_bets => PUBLIC;
+            get_betting_result => PUBLIC;
+            get_season => PUBLIC;
+            claim_referral_fees => PUBLIC;
+            get_referral_config => PUBLIC;
+            get_referrer => PUBLIC;
+            get_referrer_stats => PUBLIC;
+            sponsor_prize_pool => PUBLIC;
+            get_sponsors => PUBLIC;
+            is_sponsor_pool_locked => PUBLIC;
+            claim_achievements => PUBLIC;
+            take_snapshot => PUBLIC;
+            claim_prize => PUBLIC;
+            withdraw_vested_prize => PUBLIC;
+            get_prize_vesting => PUBLIC;
+            get_prize_claim_address => PUBLIC;
+            get_claimable_prize => PUBLIC;
+            claim_refund => PUBLIC;
+            burn_virtual_assets => PUBLIC;
+            get_status => PUBLIC;
+            get_name => PUBLIC;
+            get_fusd_address => PUBLIC;
+            get_virtual_assets => PUBLIC;
+            get_entry_fee => PUBLIC;
+            get_prize_pools => PUBLIC;
+            get_payout_table => PUBLIC;
+            get_prize => PUBLIC;
+            get_registration_start_time => PUBLIC;
+            get_registration_end_time => PUBLIC;
             get_competition_start_time => PUBLIC;
             get_competition_end_time => PUBLIC;
+            get_late_registration_policy => PUBLIC;
+            get_max_participants => PUBLIC;
+            get_registration_access => PUBLIC;
+            is_allowlisted => PUBLIC;
+            get_achievement_badge_address => PUBLIC;
+            get_user_achievements => PUBLIC;
+            get_trade_count => PUBLIC;
+            get_trade_history => PUBLIC;
+            get_user_portfolio => PUBLIC;
+            get_participants => PUBLIC;
+            get_snapshot_interval => PUBLIC;
+            get_last_snapshot_time => PUBLIC;
+            get_equity_curve => PUBLIC;
+            get_scoring_rule => PUBLIC;
+            get_risk_rules => PUBLIC;
+            get_trade_protection => PUBLIC;
+            get_leaderboard => PUBLIC;
 
             // Methods with admin access
+            set_registration_start_time => restrict_to: [admin, OWNER];
+            set_registration_end_time => restrict_to: [admin, OWNER];
+            set_late_registration_policy => restrict_to: [admin, OWNER];
+            set_max_participants => restrict_to: [admin, OWNER];
+            set_registration_access => restrict_to: [admin, OWNER];
+            add_to_allowlist => restrict_to: [admin, OWNER];
+            remove_from_allowlist => restrict_to: [admin, OWNER];
+            add_invite_codes => restrict_to: [admin, OWNER];
+            set_entry_fee => restrict_to: [admin, OWNER];
+            add_virtual_asset => restrict_to: [admin, OWNER];
+            set_payout_table => restrict_to: [admin, OWNER];
+            set_prize_vesting => restrict_to: [admin, OWNER];
+            cancel => restrict_to: [admin, OWNER];
+            settle => restrict_to: [admin, OWNER];
             set_competition_start_time => restrict_to: [admin, OWNER];
             set_competition_end_time => restrict_to: [admin, OWNER];
+            set_achievement_config => restrict_to: [admin, OWNER];
+            set_snapshot_interval => restrict_to: [admin, OWNER];
+            set_scoring_rule => restrict_to: [admin, OWNER];
+            set_risk_rules => restrict_to: [admin, OWNER];
+            set_trade_protection => restrict_to: [admin, OWNER];
+            add_perpetual_market => restrict_to: [admin, OWNER];
+            set_option_volatility => restrict_to: [admin, OWNER];
+            set_keeper_reward_config => restrict_to: [admin, OWNER];
+            set_betting_close_time => restrict_to: [admin, OWNER];
+            join_season => restrict_to: [admin, OWNER];
+            set_referral_config => restrict_to: [admin, OWNER];
+            add_sponsor => restrict_to: [admin, OWNER];
+            set_sponsor_pool_lock => restrict_to: [admin, OWNER];
+            return_sponsor_contributions => restrict_to: [admin, OWNER];
         }
     }
 
     struct Competition {
+        name: String,
         competition_data: CompetitionData,
         trade_simulator: Global<TradeSimulator>,
         user_asset_vaults: KeyValueStore<String, Owned<UserAssetVault>>,
-        fusd_resource_address: ResourceAddress,
+        fusd_resource_address: ResourceAddress, // The competition's own FUSD.
+        reference_addresses: KeyValueStore<ResourceAddress, ResourceAddress>, // Virtual to reference asset.
+        virtual_asset_addresses: Vec<ResourceAddress>,
         user_token_resource_address: ResourceAddress,
+        user_stats: KeyValueStore<String, UserStats>,
+        achievement_badge_manager: NonFungibleResourceManager,
+        achievement_configs: KeyValueStore<Achievement, AchievementConfig>,
+        unclaimed_achievements: KeyValueStore<String, NonFungibleVault>,
+        trade_history: KeyValueStore<(String, u64), TradeRecord>,
+        participants: KeyValueStore<u64, String>, // The participants by their index.
+        participant_indexes: KeyValueStore<String, u64>, // The index of each participant.
+        participant_count: u64,
+        snapshot_interval: i64, // The minimum number of seconds between two snapshots.
+        last_snapshot: Option<Instant>, // The time of the latest completed snapshot.
+        pending_snapshot: Option<(Instant, u64)>, // The time and the number of participants valued of an unfinished snapshot.
+        pending_settlement: Option<(u64, u64)>, // The number of participants valued and awarded of an unfinished settlement.
+        equity_curves: KeyValueStore<String, Vec<EquityPoint>>,
+        scoring_rule: ScoringRule,
+        risk_rules: RiskRules,
+        trade_protection: TradeProtection,
+        trade_commitments: KeyValueStore<String, (Hash, Instant)>, // The unrevealed trade of each user.
+        perpetual_markets: KeyValueStore<ResourceAddress, PerpetualMarket>,
+        perpetual_positions: KeyValueStore<(String, ResourceAddress), PerpetualPosition>,
+        option_manager: NonFungibleResourceManager,
+        option_volatilities: KeyValueStore<ResourceAddress, Decimal>, // The implied volatility of each option market.
+        option_holders: KeyValueStore<NonFungibleLocalId, String>, // The holder of each unsettled option.
+        scheduled_orders: KeyValueStore<u64, ScheduledOrder>, // Removed once completed or cancelled.
+        scheduled_order_count: u64, // The number of orders ever scheduled, used as the next order ID.
+        keeper_reward_config: KeeperRewardConfig,
+        keeper_rewards: Vault,  // The XRD paid to keepers.
+        keeper_reward_day: i64, // The trading day of the latest keeper reward.
+        keeper_rewards_on_day: u64,
+        follows: KeyValueStore<String, Follow>, // The leader each follower copies.
+        followers: KeyValueStore<String, Vec<String>>, // The followers of each leader.
+        copy_trades: KeyValueStore<(String, u64), CopyTrade>, // The trades of each leader to mirror.
+        betting_close: Option<Instant>, // Bets close at the competition start if not set.
+        bet_vault: Vault,               // The XRD staked by spectators.
+        bet_pools: KeyValueStore<String, Decimal>, // The XRD staked on each player.
+        bet_players: Vec<String>,       // The keys of `bet_pools`.
+        bets: KeyValueStore<String, Vec<(String, Decimal)>>, // The stakes of each bettor per player.
+        betting_result: Option<BettingResult>,
+        referral_config: ReferralConfig,
+        referrers: KeyValueStore<String, String>, // The referrer of each referred user, never removed.
+        referrer_stats: KeyValueStore<String, ReferrerStats>,
+        referrer_ids: Vec<String>,   // The keys of `referrer_stats`.
+        referral_fees_owed: Decimal, // The entry fees in the prize pool owed to referrers.
+        season: Option<(Global<Season>, NonFungibleVault)>, // The season results are reported to, with its badge.
+        late_registration_policy: LateRegistrationPolicy,
+        max_participants: Option<u64>,
+        registration_access: RegistrationAccess,
+        allowlist: KeyValueStore<String, ()>,
+        invite_code_hashes: KeyValueStore<Hash, ()>, // Removed once the invite code is used.
+        status: CompetitionStatus, // Only holds the final statuses, see `get_status`.
+        entry_fee_resource_address: ResourceAddress,
+        entry_fee: Decimal,
+        prize_pools: KeyValueStore<ResourceAddress, Vault>,
+        prize_resource_addresses: Vec<ResourceAddress>,
+        sponsors: KeyValueStore<u64, Sponsor>,
+        sponsor_count: u64, // The number of sponsors ever added, used as the next sponsor ID.
+        lock_sponsor_pool_at_start: bool, // Whether sponsors can only deposit before the start.
+        payout_table: Vec<Decimal>, // The share of the prize pools for each rank, best first.
+        final_leaderboard: Vec<LeaderboardEntry>, // The ranking fixed by the settlement.
+        prizes: KeyValueStore<String, Vec<(ResourceAddress, Decimal)>>,
+        prize_vesting: Option<VestingSchedule>, // Prizes are paid at once if not set.
+        prize_claim_manager: NonFungibleResourceManager,
+        burned_vault_count: u64, // The number of participants whose virtual assets are burned.
     }
 
     impl Competition {
@@ -43,23 +440,38 @@ mod competition {
         /// # Arguments
         ///
         /// * `owner_role` - The owner of the competition.
+        /// * `name` - The name of the competition, e.g. "Season 3". It is part of the names of
+        ///   the virtual assets.
+        /// * `registration_start` - The time from which users can register.
+        /// * `registration_end` - The time until which users can register. Registrations after
+        ///   the competition start are late registrations.
         /// * `competition_start` - The start time of the competition.
         /// * `competition_end` - The end time of the competition.
         /// * `trade_simulator_address` - The address of the TradeSimulator component.
-        /// * `fusd_resource_address` - The resource address of FUSD tokens.
+        /// * `fusd_reference_address` - The resource FUSD is priced by in the oracle and the
+        ///   trade simulator.
         /// * `user_token_resource_address` - The resource address of user tokens.
         pub fn instantiate(
             owner_role: OwnerRole,
+            name: String,
+            registration_start: Instant,
+            registration_end: Instant,
             competition_start: Instant,
             competition_end: Instant,
             trade_simulator_address: ComponentAddress,
-            fusd_resource_address: ResourceAddress,
+            fusd_reference_address: ResourceAddress,
             user_token_resource_address: ResourceAddress,
         ) -> (Global<Competition>, Bucket) {
+            let (address_reservation, component_address) =
+                Runtime::allocate_component_address(Competition::blueprint_id());
+
             let competition_data = CompetitionData {
+                registration_start,
+                registration_end,
                 competition_start,
                 competition_end,
             };
+            competition_data.assert_valid();
 
             let admin_badge: Bucket = ResourceBuilder::new_fungible(owner_role.clone())
                 .divisibility(DIVISIBILITY_NONE)
@@ -76,14 +488,110 @@ mod competition {
                 .mint_initial_supply(1)
                 .into();
 
+            let achievement_badge_manager =
+                ResourceBuilder::new_ruid_non_fungible::<AchievementBadge>(owner_role.clone())
+                    .metadata(metadata! {
+                        init {
+                            "name" => "Ape's Ascent Achievement", updatable;
+                            "symbol" => "ACH", updatable;
+                            "description" => "A badge awarded for reaching a milestone in a competition.", updatable;
+                            "tags" => ["badge", "achievement"], updatable;
+                            "info_url" => Url::of("https://colosseum.com"), updatable;
+                            "icon_url" => Url::of("https://colosseum.com/images/LogoColosseum.png"), updatable;
+                        }
+                    })
+                    .mint_roles(mint_roles! {
+                        minter => rule!(require(global_caller(component_address)));
+                        minter_updater => rule!(deny_all);
+                    })
+                    .create_with_no_initial_supply();
+
             let trade_simulator: Global<TradeSimulator> = trade_simulator_address.into();
 
+            let option_manager = Self::create_option_resource(component_address, &name);
+            let prize_claim_manager = Self::create_prize_claim_resource(component_address, &name);
+
+            let fusd_resource_address =
+                Self::create_virtual_asset(component_address, "FUSD", &name);
+            let reference_addresses = KeyValueStore::new();
+            reference_addresses.insert(fusd_resource_address, fusd_reference_address);
+
+            Runtime::emit_event(VirtualAssetCreatedEvent {
+                resource_address: fusd_resource_address,
+                reference_address: fusd_reference_address,
+            });
+
             let competition = Self {
+                name,
                 competition_data,
                 trade_simulator,
                 user_asset_vaults: KeyValueStore::new(),
                 fusd_resource_address,
+                reference_addresses,
+                virtual_asset_addresses: vec![fusd_resource_address],
                 user_token_resource_address,
+                user_stats: KeyValueStore::new(),
+                achievement_badge_manager,
+                achievement_configs: KeyValueStore::new(),
+                unclaimed_achievements: KeyValueStore::new(),
+                trade_history: KeyValueStore::new(),
+                participants: KeyValueStore::new(),
+                participant_indexes: KeyValueStore::new(),
+                participant_count: 0,
+                snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+                last_snapshot: None,
+                pending_snapshot: None,
+                pending_settlement: None,
+                equity_curves: KeyValueStore::new(),
+                scoring_rule: ScoringRule::HighestRoi,
+                risk_rules: RiskRules::default(),
+                trade_protection: TradeProtection::default(),
+                trade_commitments: KeyValueStore::new(),
+                perpetual_markets: KeyValueStore::new(),
+                perpetual_positions: KeyValueStore::new(),
+                option_manager,
+                option_volatilities: KeyValueStore::new(),
+                option_holders: KeyValueStore::new(),
+                scheduled_orders: KeyValueStore::new(),
+                scheduled_order_count: 0,
+                keeper_reward_config: KeeperRewardConfig::default(),
+                keeper_rewards: Vault::new(XRD),
+                keeper_reward_day: 0,
+                keeper_rewards_on_day: 0,
+                follows: KeyValueStore::new(),
+                followers: KeyValueStore::new(),
+                copy_trades: KeyValueStore::new(),
+                betting_close: None,
+                bet_vault: Vault::new(XRD),
+                bet_pools: KeyValueStore::new(),
+                bet_players: Vec::new(),
+                bets: KeyValueStore::new(),
+                betting_result: None,
+                referral_config: ReferralConfig::default(),
+                referrers: KeyValueStore::new(),
+                referrer_stats: KeyValueStore::new(),
+                referrer_ids: Vec::new(),
+                referral_fees_owed: Decimal::zero(),
+                season: None,
+                late_registration_policy: LateRegistrationPolicy::FullBalance,
+                max_participants: None,
+                registration_access: RegistrationAccess::Public,
+                allowlist: KeyValueStore::new(),
+                invite_code_hashes: KeyValueStore::new(),
+                status: CompetitionStatus::Created,
+                entry_fee_resource_address: XRD,
+                entry_fee: Decimal::zero(),
+                prize_pools: KeyValueStore::new(),
+                prize_resource_addresses: Vec::new(),
+                sponsors: KeyValueStore::new(),
+                sponsor_count: 0,
+                lock_sponsor_pool_at_start: true,
+                payout_table: vec![Decimal::one()],
+                final_leaderboard: Vec::new(),
+                prizes: KeyValueStore::new(),
+                prize_vesting: None,
+                prize_claim_manager,
+                burned_vault_count: 0,
             }
             .instantiate()
             .prepare_to_globalize(owner_role.clone())
@@ -94,104 +602,323 @@ mod competition {
                     )
                 );
             ))
+            .with_address(address_reservation)
             .globalize();
 
             (competition, admin_badge)
         }
 
-        /// Registers a user for the competition by minting initial FUSD and creating a user asset vault.
+        /// Registers a user for a public or allowlisted competition.
         ///
         /// # Arguments
         ///
         /// * `user_token_proof` - A proof of the user's token to verify identity.
-        pub fn register(&mut self, user_token_proof: Proof) {
-            self.assert_competition_not_started();
+        /// * `payment` - The entry fee, if the competition charges one.
+        /// * `referrer_id` - The ID of the user who referred the player, if any.
+        ///
+        /// # Returns
+        ///
+        /// The change of the payment.
+        pub fn register(
+            &mut self,
+            user_token_proof: Proof,
+            payment: Option<Bucket>,
+            referrer_id: Option<String>,
+        ) -> Option<Bucket> {
             let user_id = self.extract_user_id(user_token_proof);
+            match self.registration_access {
+                RegistrationAccess::Public => {}
+                RegistrationAccess::Allowlist => assert!(
+                    self.is_allowlisted(user_id.clone()),
+                    "User is not on the allowlist."
+                ),
+                _ => panic!("The competition requires a badge or an invite code."),
+            }
 
-            // Mint FUSD
-            let fusd_bucket = ResourceManager::from_address(self.fusd_resource_address)
-                .mint(Decimal::from(10000));
-
-            let user_asset_vault = UserAssetVault::instantiate(fusd_bucket);
-
-            self.user_asset_vaults.insert(user_id, user_asset_vault);
+            self.register_user(user_id, payment, referrer_id)
         }
 
-        /// Allows a user to trade assets during the competition.
+        /// Registers a user for a competition that requires a badge.
         ///
         /// # Arguments
         ///
         /// * `user_token_proof` - A proof of the user's token to verify identity.
-        /// * `from_address` - The resource address of the asset to be traded from.
-        /// * `to_address` - The resource address of the asset to be traded to.
-        /// * `amount` - The amount of the asset to be traded.
-        pub fn trade(
+        /// * `badge_proof` - A proof of the badge required by the competition.
+        /// * `payment` - The entry fee, if the competition charges one.
+        /// * `referrer_id` - The ID of the user who referred the player, if any.
+        ///
+        /// # Returns
+        ///
+        /// The change of the payment.
+        pub fn register_with_badge(
             &mut self,
             user_token_proof: Proof,
-            from_address: ResourceAddress,
-            to_address: ResourceAddress,
-            amount: Decimal,
-        ) {
-            self.assert_competition_running();
-            info!(
-                "I want to trade {:?} of {:?} into {:?}",
-                amount, from_address, to_address
+            badge_proof: Proof,
+            payment: Option<Bucket>,
+            referrer_id: Option<String>,
+        ) -> Option<Bucket> {
+            let RegistrationAccess::Badge(badge_address) = self.registration_access else {
+                panic!("The competition does not require a badge.");
+            };
+            let checked_badge_proof = badge_proof.check(badge_address);
+            assert!(
+                checked_badge_proof.amount().is_positive(),
+                "The badge proof is empty."
             );
 
             let user_id = self.extract_user_id(user_token_proof);
-
-            // Withdraw asset from the user vault
-            let user_asset_vault = self
-                .user_asset_vaults
-                .get(&user_id)
-                .expect("User vault not found");
-            let from_token_bucket = user_asset_vault.withdraw_asset(from_address, amount);
-
-            // Swap asset
-            let to_token_bucket = self.trade_simulator.trade(from_token_bucket, to_address);
-
-            // Deposit new assets back to the user vault
-            user_asset_vault.deposit_asset(to_token_bucket);
+            self.register_user(user_id, payment, referrer_id)
         }
 
-        /// Asserts that the competition has not started yet.
-        fn assert_competition_not_started(&self) {
-            info!(
-                "Current time: {:?}",
-                Clock::current_time(TimePrecisionV2::Second)
-            );
-            info!(
-                "Current competition start: {:?}",
-                self.competition_data.competition_start
+        /// Registers a user for an invite-only competition. Each invite code can be used once.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `invite_code` - The invite code handed out by the organizer.
+        /// * `payment` - The entry fee, if the competition charges one.
+        /// * `referrer_id` - The ID of the user who referred the player, if any.
+        ///
+        /// # Returns
+        ///
+        /// The change of the payment.
+        pub fn register_with_invite_code(
+            &mut self,
+            user_token_proof: Proof,
+            invite_code: String,
+            payment: Option<Bucket>,
+            referrer_id: Option<String>,
+        ) -> Option<Bucket> {
+            assert!(
+                self.registration_access == RegistrationAccess::InviteCode,
+                "The competition is not invite-only."
             );
             assert!(
-                Clock::current_time_is_strictly_before(
-                    self.competition_data.competition_start,
-                    TimePrecisionV2::Second,
-                ),
-                "Competition has already started. Registration is not possible anymore!"
+                self.invite_code_hashes
+                    .remove(&hash(invite_code.as_bytes()))
+                    .is_some(),
+                "Invalid invite code."
             );
+
+            let user_id = self.extract_user_id(user_token_proof);
+            self.register_user(user_id, payment, referrer_id)
         }
 
-        /// Asserts that the competition is currently running.
-        fn assert_competition_running(&self) {
+        /// Registers a user by collecting the entry fee, minting initial FUSD and creating a user
+        /// asset vault.
+        ///
+        /// Users registering after the competition started receive the starting balance
+        /// defined by the late registration policy. Spectators who placed bets cannot register.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user to register.
+        /// * `payment` - The entry fee, if the competition charges one.
+        /// * `referrer_id` - The ID of the user who referred the player, if any.
+        ///
+        /// # Returns
+        ///
+        /// The change of the payment.
+        fn register_user(
+            &mut self,
+            user_id: String,
+            payment: Option<Bucket>,
+            referrer_id: Option<String>,
+        ) -> Option<Bucket> {
+            self.assert_registration_open();
             assert!(
-                Clock::current_time_is_at_or_after(
-                    self.get_competition_start_time(),
-                    TimePrecisionV2::Second
-                ),
-                "Competition has not started yet!"
+                !self.is_participant(&user_id),
+                "User is already registered."
             );
             assert!(
-                Clock::current_time_is_strictly_before(
-                    self.get_competition_end_time(),
-                    TimePrecisionV2::Second
-                ),
-                "Competition has already finished."
+                self.bets.get(&user_id).is_none(),
+                "Users who placed bets cannot join the competition."
+            );
+            if let Some(max_participants) = self.max_participants {
+                assert!(
+                    self.participant_count < max_participants,
+                    "The competition is full."
+                );
+            }
+
+            let change = if self.entry_fee.is_zero() {
+                payment
+            } else {
+                let mut payment = payment.expect("The competition requires an entry fee.");
+                assert!(
+                    payment.resource_address() == self.entry_fee_resource_address,
+                    "The entry fee must be paid in the entry fee resource."
+                );
+                assert!(
+                    payment.amount() >= self.entry_fee,
+                    "The payment does not cover the entry fee."
+                );
+                self.deposit_to_prize_pool(payment.take(self.entry_fee));
+                Some(payment)
+            };
+
+            let referral_fee = self.record_referral(&user_id, referrer_id);
+
+            self.add_participant(&user_id);
+
+            // Mint FUSD
+            let starting_balance = self.calculate_starting_balance();
+            let fusd_bucket =
+                FungibleResourceManager::from(self.fusd_resource_address).mint(starting_balance);
+
+            // Users who unregistered and come back get their emptied vault back.
+            let user_asset_vault = self.user_asset_vaults.get(&user_id);
+            if let Some(user_asset_vault) = user_asset_vault {
+                user_asset_vault.deposit_asset(fusd_bucket.into());
+            } else {
+                drop(user_asset_vault);
+                self.user_asset_vaults.insert(
+                    user_id.clone(),
+                    UserAssetVault::instantiate(fusd_bucket.into()),
+                );
+            }
+            self.equity_curves.insert(user_id.clone(), Vec::new());
+            self.user_stats.insert(
+                user_id.clone(),
+                UserStats {
+                    starting_balance,
+                    entry_fee_paid: self.entry_fee,
+                    referral_fee,
+                    ..Default::default()
+                },
+            );
+
+            Runtime::emit_event(UserRegisteredEvent {
+                user_id,
+                starting_balance,
+            });
+
+            change
+        }
+
+        /// Records who referred a user and credits the referrer. A user keeps their first
+        /// referrer for good, so the referral count and bonus balance are only credited once,
+        /// even if the user unregisters and registers again.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the registering user.
+        /// * `referrer_id` - The referrer named at registration.
+        ///
+        /// # Returns
+        ///
+        /// The share of the user's entry fee owed to the referrer, zero if the user was not
+        /// referred.
+        fn record_referral(&mut self, user_id: &str, referrer_id: Option<String>) -> Decimal {
+            let user_id = user_id.to_string();
+            let stored_referrer_id = self
+                .referrers
+                .get(&user_id)
+                .map(|referrer_id| referrer_id.clone());
+            let (referrer_id, is_new) = match (stored_referrer_id, referrer_id) {
+                (Some(stored_referrer_id), referrer_id) => {
+                    assert!(
+                        referrer_id.is_none_or(|referrer_id| referrer_id == stored_referrer_id),
+                        "The user was already referred by another user."
+                    );
+                    (stored_referrer_id, false)
+                }
+                (None, Some(referrer_id)) => {
+                    self.assert_valid_referrer(&user_id, &referrer_id);
+                    self.referrers.insert(user_id.clone(), referrer_id.clone());
+                    (referrer_id, true)
+                }
+                (None, None) => return Decimal::zero(),
+            };
+
+            let fee = if self.entry_fee.is_zero() {
+                Decimal::zero()
+            } else {
+                let divisibility = ResourceManager::from(self.entry_fee_resource_address)
+                    .resource_type()
+                    .divisibility()
+                    .expect("The entry fee must be fungible");
+                (self.entry_fee * self.referral_config.fee_share)
+                    .checked_round(divisibility, RoundingMode::ToZero)
+                    .expect("Rounding error")
+            };
+            self.referral_fees_owed += fee;
+
+            // Referrers who play receive the bonus balance for each new referral
+            let bonus_balance = self.referral_config.bonus_balance;
+            let bonus_balance =
+                if is_new && bonus_balance.is_positive() && self.is_participant(&referrer_id) {
+                    let fusd_bucket = FungibleResourceManager::from(self.fusd_resource_address)
+                        .mint(bonus_balance);
+                    self.user_asset_vaults
+                        .get(&referrer_id)
+                        .expect("User vault not found")
+                        .deposit_asset(fusd_bucket.into());
+                    self.user_stats
+                        .get_mut(&referrer_id)
+                        .expect("User stats not found")
+                        .referral_bonus += bonus_balance;
+                    bonus_balance
+                } else {
+                    Decimal::zero()
+                };
+
+            let referrer_stats = self.referrer_stats.get_mut(&referrer_id);
+            if let Some(mut referrer_stats) = referrer_stats {
+                referrer_stats.referral_count += is_new as u64;
+                referrer_stats.fees_earned += fee;
+                referrer_stats.bonus_balance += bonus_balance;
+            } else {
+                drop(referrer_stats);
+                self.referrer_stats.insert(
+                    referrer_id.clone(),
+                    ReferrerStats {
+                        referral_count: 1,
+                        fees_earned: fee,
+                        fees_claimed: Decimal::zero(),
+                        bonus_balance,
+                    },
+                );
+                self.referrer_ids.push(referrer_id.clone());
+            }
+
+            Runtime::emit_event(ReferralRecordedEvent {
+                user_id,
+                referrer_id,
+                fee,
+                bonus_balance,
+            });
+
+            fee
+        }
+
+        /// Asserts that a user can name a referrer. The referrer must be registered, so that
+        /// referral fees are only owed to players who can claim them. Users cannot refer
+        /// themselves, directly or through a chain of referrals.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the registering user.
+        /// * `referrer_id` - The referrer named at registration.
+        fn assert_valid_referrer(&self, user_id: &str, referrer_id: &str) {
+            assert!(user_id != referrer_id, "Users cannot refer themselves.");
+            assert!(
+                self.is_participant(referrer_id),
+                "The referrer is not registered."
             );
+
+            let mut current_id = referrer_id.to_string();
+            while let Some(next_id) = self.referrers.get(&current_id).map(|id| id.clone()) {
+                assert!(next_id != user_id, "Circular referrals are not allowed.");
+                current_id = next_id;
+            }
         }
 
-        /// Extracts the user ID from the provided proof.
+        /// Removes a user from the competition before it starts.
+        ///
+        /// The user's virtual assets are burned and the entry fee is paid back. The emptied user
+        /// asset vault stays in the store, because stored components cannot be dropped, and is
+        /// reused if the user registers again. Used invite codes stay used.
         ///
         /// # Arguments
         ///
@@ -199,49 +926,4146 @@ mod competition {
         ///
         /// # Returns
         ///
-        /// A string representing the user ID.
-        fn extract_user_id(&self, user_token_proof: Proof) -> String {
-            let checked_proof = user_token_proof.check(self.user_token_resource_address);
-            checked_proof
-                .as_non_fungible()
-                .non_fungible_local_id()
-                .to_string()
+        /// A bucket containing the entry fee.
+        pub fn unregister(&mut self, user_token_proof: Proof) -> Bucket {
+            self.assert_competition_not_started();
+            let user_id = self.extract_user_id(user_token_proof);
+            assert!(self.is_participant(&user_id), "User is not registered.");
+
+            let refund = self.refund_entry_fee(&user_id);
+
+            self.user_asset_vaults
+                .get(&user_id)
+                .expect("User vault not found")
+                .burn_all_assets();
+
+            self.remove_follows(&user_id);
+            self.remove_participant(&user_id);
+            self.equity_curves.remove(&user_id);
+            self.user_stats.remove(&user_id);
+
+            Runtime::emit_event(UserUnregisteredEvent { user_id });
+
+            refund
         }
 
-        /// Sets the competition start time.
+        /// Checks whether a user is registered for the competition.
         ///
         /// # Arguments
         ///
-        /// * `time` - The new start time of the competition.
-        pub fn set_competition_start_time(&mut self, time: Instant) {
-            self.competition_data.competition_start = time;
+        /// * `user_id` - The ID of the user.
+        fn is_participant(&self, user_id: &str) -> bool {
+            self.participant_indexes.get(&user_id.to_string()).is_some()
         }
 
-        /// Sets the competition end time.
+        /// Appends a user to the participants.
         ///
         /// # Arguments
         ///
-        /// * `time` - The new end time of the competition.
-        pub fn set_competition_end_time(&mut self, time: Instant) {
-            self.competition_data.competition_end = time;
+        /// * `user_id` - The ID of the registering user.
+        fn add_participant(&mut self, user_id: &str) {
+            let index = self.participant_count;
+            self.participants.insert(index, user_id.to_string());
+            self.participant_indexes.insert(user_id.to_string(), index);
+            self.participant_count += 1;
         }
 
-        /// Gets the competition start time.
+        /// Removes a user from the participants. The last participant takes the place of the
+        /// removed one, so no other index changes.
         ///
-        /// # Returns
+        /// # Arguments
         ///
-        /// The start time of the competition.
-        pub fn get_competition_start_time(&self) -> Instant {
-            self.competition_data.competition_start
+        /// * `user_id` - The ID of the leaving user.
+        fn remove_participant(&mut self, user_id: &str) {
+            let index = self
+                .participant_indexes
+                .remove(&user_id.to_string())
+                .expect("User is not registered.");
+            self.participant_count -= 1;
+            let last_id = self
+                .participants
+                .remove(&self.participant_count)
+                .expect("Participant not found");
+            if index != self.participant_count {
+                self.participants.insert(index, last_id.clone());
+                self.participant_indexes.insert(last_id, index);
+            }
         }
 
-        /// Gets the competition end time.
+        /// Allows a user to trade assets during the competition.
         ///
-        /// # Returns
+        /// # Arguments
         ///
-        /// The end time of the competition.
-        pub fn get_competition_end_time(&self) -> Instant {
-            self.competition_data.competition_end
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `from_address` - The resource address of the asset to be traded from.
+        /// * `to_address` - The resource address of the asset to be traded to.
+        /// * `amount` - The amount of the asset to be traded.
+        pub fn trade(
+            &mut self,
+            user_token_proof: Proof,
+            from_address: ResourceAddress,
+            to_address: ResourceAddress,
+            amount: Decimal,
+        ) {
+            self.assert_competition_running();
+            assert!(
+                self.trade_protection.reveal_delay.is_none(),
+                "Trades must be committed first in this competition."
+            );
+            let user_id = self.extract_user_id(user_token_proof);
+
+            self.execute_trade(user_id, from_address, to_address, amount);
+        }
+
+        /// Commits to a trade without disclosing it. Only used if the competition requires
+        /// commit-reveal trades. A new commitment replaces an unrevealed one.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `commitment` - The hash of the trade, see `risk::trade_commitment`.
+        pub fn commit_trade(&mut self, user_token_proof: Proof, commitment: Hash) {
+            self.assert_competition_running();
+            assert!(
+                self.trade_protection.reveal_delay.is_some(),
+                "Trades do not need to be committed in this competition."
+            );
+            let user_id = self.extract_user_id(user_token_proof);
+            assert!(
+                self.user_asset_vaults.get(&user_id).is_some(),
+                "User is not registered."
+            );
+
+            let committed_at = Clock::current_time(TimePrecisionV2::Second);
+            self.trade_commitments
+                .insert(user_id.clone(), (commitment, committed_at));
+
+            Runtime::emit_event(TradeCommittedEvent {
+                user_id,
+                commitment,
+            });
+        }
+
+        /// Reveals and executes a previously committed trade. The commitment expires if it is not
+        /// revealed within `risk::REVEAL_WINDOW` seconds after the reveal delay.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `from_address` - The resource address of the asset to be traded from.
+        /// * `to_address` - The resource address of the asset to be traded to.
+        /// * `amount` - The amount of the asset to be traded.
+        /// * `salt` - The secret used to compute the commitment.
+        pub fn reveal_trade(
+            &mut self,
+            user_token_proof: Proof,
+            from_address: ResourceAddress,
+            to_address: ResourceAddress,
+            amount: Decimal,
+            salt: String,
+        ) {
+            self.assert_competition_running();
+            assert!(
+                self.trade_protection.reveal_delay.is_some(),
+                "Trades do not need to be committed in this competition."
+            );
+            let user_id = self.extract_user_id(user_token_proof);
+
+            let (commitment, committed_at) = self
+                .trade_commitments
+                .remove(&user_id)
+                .expect("No trade has been committed.");
+            assert_eq!(
+                commitment,
+                trade_commitment(from_address, to_address, amount, &salt),
+                "The trade does not match the commitment."
+            );
+            self.trade_protection
+                .assert_revealable(committed_at, Clock::current_time(TimePrecisionV2::Second));
+
+            self.execute_trade(user_id, from_address, to_address, amount);
+        }
+
+        /// Opens a leveraged perpetual position. The margin is taken from the user's FUSD. The
+        /// position counts as a trade of its leveraged notional towards the risk rules.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `resource_address` - The virtual asset of the perpetual market.
+        /// * `side` - Whether to go long or short.
+        /// * `margin` - The FUSD to put up as collateral.
+        /// * `leverage` - The multiple of the margin to open the position with.
+        pub fn open_position(
+            &mut self,
+            user_token_proof: Proof,
+            resource_address: ResourceAddress,
+            side: PositionSide,
+            margin: Decimal,
+            leverage: Decimal,
+        ) {
+            self.assert_competition_running();
+            let user_id = self.extract_user_id(user_token_proof);
+
+            let mut market = self
+                .perpetual_markets
+                .get(&resource_address)
+                .expect("No perpetual market for this asset")
+                .clone();
+            assert!(margin.is_positive(), "The margin must be positive.");
+            assert!(
+                leverage >= Decimal::one() && leverage <= market.config.max_leverage,
+                "The leverage must be between 1 and {}.",
+                market.config.max_leverage
+            );
+            assert!(
+                self.perpetual_positions
+                    .get(&(user_id.clone(), resource_address))
+                    .is_none(),
+                "A position in this asset is already open."
+            );
+            let notional = margin * leverage;
+            self.risk_rules.assert_trade_value(notional);
+            self.assert_trade_timing(&user_id, self.fusd_resource_address, resource_address);
+            self.count_daily_trade(&user_id);
+            self.record_trade_time(&user_id);
+
+            // Lock the margin
+            self.user_asset_vaults
+                .get(&user_id)
+                .expect("User vault not found")
+                .withdraw_asset(self.fusd_resource_address, margin)
+                .burn();
+
+            let mark_price = self.get_mark_price(resource_address);
+            market.apply_funding(mark_price, Clock::current_time(TimePrecisionV2::Second));
+            let size = notional
+                .checked_div(mark_price)
+                .expect("Division by zero error");
+            market.change_open_interest(side, size);
+
+            let position = PerpetualPosition {
+                side,
+                size,
+                entry_price: mark_price,
+                margin,
+                entry_funding_index: market.funding_index,
+            };
+            self.perpetual_markets.insert(resource_address, market);
+            self.perpetual_positions
+                .insert((user_id.clone(), resource_address), position);
+            self.user_stats
+                .get_mut(&user_id)
+                .expect("User stats not found")
+                .perpetual_assets
+                .push(resource_address);
+            if self.risk_rules.max_position_share.is_some() {
+                self.assert_position_within_limit(&user_id, resource_address, self.get_prices());
+            }
+
+            Runtime::emit_event(PerpetualPositionOpenedEvent {
+                user_id,
+                resource_address,
+                side,
+                size,
+                entry_price: mark_price,
+                margin,
+            });
+        }
+
+        /// Closes a perpetual position and credits its equity to the user's FUSD. Positions below
+        /// the maintenance margin cannot be closed, they can only be liquidated.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `resource_address` - The virtual asset of the perpetual market.
+        pub fn close_position(
+            &mut self,
+            user_token_proof: Proof,
+            resource_address: ResourceAddress,
+        ) {
+            self.assert_competition_running();
+            let user_id = self.extract_user_id(user_token_proof);
+            assert!(
+                !self.is_position_liquidatable(&user_id, resource_address),
+                "The position is below the maintenance margin."
+            );
+            self.assert_trade_timing(&user_id, resource_address, self.fusd_resource_address);
+            self.record_trade_time(&user_id);
+
+            self.remove_perpetual_position(&user_id, resource_address, false);
+        }
+
+        /// Liquidates a perpetual position whose equity has fallen below the maintenance margin.
+        /// The remaining margin is lost and the user no longer survives the competition. Anyone
+        /// can call this method.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user holding the position.
+        /// * `resource_address` - The virtual asset of the perpetual market.
+        /// * `keeper_token_proof` - A proof of the keeper's user token, needed to receive the
+        ///   keeper reward.
+        ///
+        /// # Returns
+        ///
+        /// The keeper reward, empty if no reward is paid.
+        pub fn liquidate_position(
+            &mut self,
+            user_id: String,
+            resource_address: ResourceAddress,
+            keeper_token_proof: Option<Proof>,
+        ) -> Bucket {
+            self.assert_competition_running();
+            assert!(
+                self.is_position_liquidatable(&user_id, resource_address),
+                "The position is above the maintenance margin."
+            );
+            let margin = self
+                .perpetual_positions
+                .get(&(user_id.clone(), resource_address))
+                .expect("No open position in this asset")
+                .margin;
+
+            self.remove_perpetual_position(&user_id, resource_address, true);
+
+            self.pay_keeper_reward(
+                KeeperAction::Liquidation,
+                keeper_token_proof,
+                Some((&user_id, margin)),
+            )
+        }
+
+        /// Applies the funding payments of all funding intervals that have passed. Funding is
+        /// also applied whenever a position is opened or closed, so this method only needs to
+        /// be called to keep the funding index up to date in quiet markets.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The virtual asset of the perpetual market.
+        pub fn apply_funding(&mut self, resource_address: ResourceAddress) {
+            self.assert_competition_running();
+
+            let mark_price = self.get_mark_price(resource_address);
+            let mut market = self
+                .perpetual_markets
+                .get_mut(&resource_address)
+                .expect("No perpetual market for this asset");
+            market.apply_funding(mark_price, Clock::current_time(TimePrecisionV2::Second));
+        }
+
+        /// Gets the state of a perpetual market.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The virtual asset of the perpetual market.
+        ///
+        /// # Returns
+        ///
+        /// The parameters, open interest and funding index of the market.
+        pub fn get_perpetual_market(&self, resource_address: ResourceAddress) -> PerpetualMarket {
+            self.perpetual_markets
+                .get(&resource_address)
+                .expect("No perpetual market for this asset")
+                .clone()
+        }
+
+        /// Gets the open perpetual positions of a user, valued at the current mark prices.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// The positions with their unrealized PnL, funding and equity in FUSD.
+        pub fn get_perpetual_positions(&self, user_id: String) -> Vec<PerpetualPositionValue> {
+            self.value_perpetual_positions(&user_id, &self.get_prices())
+        }
+
+        /// Closes a perpetual position and, unless it is liquidated, credits its equity to the
+        /// user's FUSD.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user holding the position.
+        /// * `resource_address` - The virtual asset of the perpetual market.
+        /// * `liquidated` - Whether the position is liquidated.
+        fn remove_perpetual_position(
+            &mut self,
+            user_id: &str,
+            resource_address: ResourceAddress,
+            liquidated: bool,
+        ) {
+            let position = self
+                .perpetual_positions
+                .remove(&(user_id.to_string(), resource_address))
+                .expect("No open position in this asset");
+
+            let mark_price = self.get_mark_price(resource_address);
+            let equity = {
+                let mut market = self
+                    .perpetual_markets
+                    .get_mut(&resource_address)
+                    .expect("No perpetual market for this asset");
+                market.apply_funding(mark_price, Clock::current_time(TimePrecisionV2::Second));
+                market.change_open_interest(position.side, -position.size);
+                position.equity(mark_price, market.funding_index)
+            };
+
+            let payout = if liquidated {
+                Decimal::zero()
+            } else {
+                equity.max(Decimal::zero())
+            };
+            if payout.is_positive() {
+                let fusd = FungibleResourceManager::from(self.fusd_resource_address).mint(payout);
+                self.user_asset_vaults
+                    .get(&user_id.to_string())
+                    .expect("User vault not found")
+                    .deposit_asset(fusd.into());
+            }
+
+            {
+                let mut user_stats = self
+                    .user_stats
+                    .get_mut(&user_id.to_string())
+                    .expect("User stats not found");
+                user_stats
+                    .perpetual_assets
+                    .retain(|address| *address != resource_address);
+                user_stats.liquidated |= liquidated;
+            }
+
+            Runtime::emit_event(PerpetualPositionClosedEvent {
+                user_id: user_id.to_string(),
+                resource_address,
+                realized_pnl: payout - position.margin,
+                liquidated,
+            });
+        }
+
+        /// Checks whether a perpetual position has fallen below the maintenance margin.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user holding the position.
+        /// * `resource_address` - The virtual asset of the perpetual market.
+        fn is_position_liquidatable(
+            &self,
+            user_id: &str,
+            resource_address: ResourceAddress,
+        ) -> bool {
+            let position = self
+                .perpetual_positions
+                .get(&(user_id.to_string(), resource_address))
+                .expect("No open position in this asset")
+                .clone();
+            let market = self
+                .perpetual_markets
+                .get(&resource_address)
+                .expect("No perpetual market for this asset")
+                .clone();
+            let mark_price = self.get_mark_price(resource_address);
+            let funding_index = market
+                .current_funding_index(mark_price, Clock::current_time(TimePrecisionV2::Second));
+            position.is_liquidatable(mark_price, funding_index, market.config.maintenance_margin)
+        }
+
+        /// Values the open perpetual positions of a user.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        /// * `prices` - The FUSD price of every virtual asset.
+        ///
+        /// # Returns
+        ///
+        /// The positions with their unrealized PnL, funding and equity in FUSD.
+        fn value_perpetual_positions(
+            &self,
+            user_id: &str,
+            prices: &IndexMap<ResourceAddress, Decimal>,
+        ) -> Vec<PerpetualPositionValue> {
+            let perpetual_assets = match self.user_stats.get(&user_id.to_string()) {
+                Some(user_stats) => user_stats.perpetual_assets.clone(),
+                None => return Vec::new(),
+            };
+            let now = Clock::current_time(TimePrecisionV2::Second);
+
+            perpetual_assets
+                .into_iter()
+                .map(|resource_address| {
+                    let position = self
+                        .perpetual_positions
+                        .get(&(user_id.to_string(), resource_address))
+                        .expect("Perpetual position not found")
+                        .clone();
+                    let mark_price = *prices.get(&resource_address).expect("Price not found");
+                    let funding_index = self
+                        .perpetual_markets
+                        .get(&resource_address)
+                        .expect("No perpetual market for this asset")
+                        .current_funding_index(mark_price, now);
+
+                    PerpetualPositionValue {
+                        resource_address,
+                        mark_price,
+                        unrealized_pnl: position.unrealized_pnl(mark_price),
+                        funding_paid: position.funding_paid(funding_index),
+                        equity: position.equity(mark_price, funding_index),
+                        position,
+                    }
+                })
+                .collect()
+        }
+
+        /// Buys a European option on a virtual asset. The premium is the Black-Scholes price
+        /// and is paid from the user's FUSD. The option is kept in the user's asset vault. The
+        /// premium counts as a trade towards the risk rules.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `resource_address` - The virtual asset the option is written on.
+        /// * `kind` - Whether to buy a call or a put.
+        /// * `strike` - The FUSD price the payoff is measured against.
+        /// * `expiry` - The time the option is cash-settled at, at the latest the competition end.
+        /// * `quantity` - The number of units of the underlying the option covers.
+        ///
+        /// # Returns
+        ///
+        /// The local ID of the option.
+        pub fn buy_option(
+            &mut self,
+            user_token_proof: Proof,
+            resource_address: ResourceAddress,
+            kind: OptionKind,
+            strike: Decimal,
+            expiry: Instant,
+            quantity: Decimal,
+        ) -> NonFungibleLocalId {
+            self.assert_competition_running();
+            assert!(strike.is_positive(), "The strike must be positive.");
+            let user_id = self.extract_user_id(user_token_proof);
+
+            assert!(
+                self.user_stats
+                    .get(&user_id)
+                    .expect("User is not registered.")
+                    .option_ids
+                    .len()
+                    < MAX_OPEN_OPTIONS,
+                "A user can hold at most {} unsettled options.",
+                MAX_OPEN_OPTIONS
+            );
+
+            let premium = self.quote_option(resource_address, kind, strike, expiry, quantity);
+            assert!(premium.is_positive(), "The option is worthless.");
+            self.risk_rules.assert_trade_value(premium);
+            self.assert_trade_timing(&user_id, self.fusd_resource_address, resource_address);
+            self.count_daily_trade(&user_id);
+
+            // Pay the premium
+            let user_asset_vault = self
+                .user_asset_vaults
+                .get(&user_id)
+                .expect("User vault not found");
+            user_asset_vault
+                .withdraw_asset(self.fusd_resource_address, premium)
+                .burn();
+
+            let option = self.option_manager.mint_ruid_non_fungible(OptionContract {
+                underlying: resource_address,
+                kind,
+                strike,
+                expiry,
+                quantity,
+                premium,
+            });
+            let option_id = option.non_fungible_local_id();
+            user_asset_vault.deposit_asset(option.into());
+            drop(user_asset_vault);
+
+            self.option_holders
+                .insert(option_id.clone(), user_id.clone());
+            self.user_stats
+                .get_mut(&user_id)
+                .expect("User stats not found")
+                .option_ids
+                .push(option_id.clone());
+            if self.risk_rules.max_position_share.is_some() {
+                self.assert_position_within_limit(&user_id, resource_address, self.get_prices());
+            }
+
+            Runtime::emit_event(OptionBoughtEvent {
+                user_id,
+                option_id: option_id.clone(),
+                underlying: resource_address,
+                kind,
+                strike,
+                expiry,
+                quantity,
+                premium,
+            });
+
+            option_id
+        }
+
+        /// Cash-settles an expired option against the mark price of its underlying at expiry. The
+        /// option is burned and its payoff is credited to the holder's FUSD. As the payoff does
+        /// not depend on when the option is settled, anyone can call this method at any time
+        /// until the competition is settled or cancelled.
+        ///
+        /// # Arguments
+        ///
+        /// * `option_id` - The local ID of the option.
+        /// * `keeper_token_proof` - A proof of the keeper's user token, needed to receive the
+        ///   keeper reward.
+        ///
+        /// # Returns
+        ///
+        /// The keeper reward, empty if no reward is paid.
+        pub fn settle_option(
+            &mut self,
+            option_id: NonFungibleLocalId,
+            keeper_token_proof: Option<Proof>,
+        ) -> Bucket {
+            assert!(
+                !self.get_status().is_final(),
+                "Competition has already been settled or cancelled."
+            );
+
+            let user_id = self
+                .option_holders
+                .remove(&option_id)
+                .expect("No unsettled option with this ID");
+            let contract: OptionContract = self.option_manager.get_non_fungible_data(&option_id);
+            assert!(
+                Clock::current_time_is_at_or_after(contract.expiry, TimePrecisionV2::Second),
+                "The option has not expired yet."
+            );
+
+            let settlement_price = self.get_mark_price_at(contract.underlying, contract.expiry);
+            let payoff = contract.payoff(settlement_price);
+
+            let user_asset_vault = self
+                .user_asset_vaults
+                .get(&user_id)
+                .expect("User vault not found");
+            user_asset_vault
+                .withdraw_non_fungible(self.option_manager.address(), option_id.clone())
+                .burn();
+            if payoff.is_positive() {
+                let fusd = FungibleResourceManager::from(self.fusd_resource_address).mint(payoff);
+                user_asset_vault.deposit_asset(fusd.into());
+            }
+            drop(user_asset_vault);
+
+            self.user_stats
+                .get_mut(&user_id)
+                .expect("User stats not found")
+                .option_ids
+                .retain(|id| *id != option_id);
+
+            Runtime::emit_event(OptionSettledEvent {
+                user_id: user_id.clone(),
+                option_id,
+                settlement_price,
+                payoff,
+            });
+
+            self.pay_keeper_reward(
+                KeeperAction::OptionSettlement,
+                keeper_token_proof,
+                Some((&user_id, contract.premium)),
+            )
+        }
+
+        /// Prices an option at the current oracle price and the implied volatility set by the
+        /// admin.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The virtual asset the option is written on.
+        /// * `kind` - Whether the option is a call or a put.
+        /// * `strike` - The FUSD price the payoff is measured against.
+        /// * `expiry` - The time the option is cash-settled at, at the latest the competition end.
+        /// * `quantity` - The number of units of the underlying the option covers.
+        ///
+        /// # Returns
+        ///
+        /// The premium of the option in FUSD.
+        pub fn quote_option(
+            &self,
+            resource_address: ResourceAddress,
+            kind: OptionKind,
+            strike: Decimal,
+            expiry: Instant,
+            quantity: Decimal,
+        ) -> Decimal {
+            let volatility = self.get_option_volatility(resource_address);
+            assert!(strike.is_positive(), "The strike must be positive.");
+            assert!(quantity.is_positive(), "The quantity must be positive.");
+            assert!(
+                Clock::current_time_is_strictly_before(expiry, TimePrecisionV2::Second),
+                "The expiry must be in the future."
+            );
+            assert!(
+                expiry <= self.competition_data.competition_end,
+                "The option must expire before the competition ends."
+            );
+
+            OptionContract {
+                underlying: resource_address,
+                kind,
+                strike,
+                expiry,
+                quantity,
+                premium: Decimal::zero(),
+            }
+            .value(
+                self.get_mark_price(resource_address),
+                volatility,
+                Clock::current_time(TimePrecisionV2::Second),
+            )
+        }
+
+        /// Gets the resource address of the options of this competition.
+        ///
+        /// # Returns
+        ///
+        /// The resource address of the option NFTs.
+        pub fn get_option_address(&self) -> ResourceAddress {
+            self.option_manager.address()
+        }
+
+        /// Gets the implied volatility options on a virtual asset are priced with.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The virtual asset the options are written on.
+        ///
+        /// # Returns
+        ///
+        /// The annualized implied volatility, e.g. 0.8 for 80%.
+        pub fn get_option_volatility(&self, resource_address: ResourceAddress) -> Decimal {
+            *self
+                .option_volatilities
+                .get(&resource_address)
+                .expect("No options are traded on this asset")
+        }
+
+        /// Gets the unsettled options of a user, valued at the current oracle prices.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// The options with their current value in FUSD.
+        pub fn get_option_positions(&self, user_id: String) -> Vec<OptionPositionValue> {
+            self.value_option_positions(&user_id, &self.get_prices())
+        }
+
+        /// Values the unsettled options of a user. Unexpired options are priced with
+        /// Black-Scholes, expired options at their payoff at expiry until they are settled.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        /// * `prices` - The FUSD price of every virtual asset.
+        ///
+        /// # Returns
+        ///
+        /// The options with their current value in FUSD.
+        fn value_option_positions(
+            &self,
+            user_id: &str,
+            prices: &IndexMap<ResourceAddress, Decimal>,
+        ) -> Vec<OptionPositionValue> {
+            let option_ids = match self.user_stats.get(&user_id.to_string()) {
+                Some(user_stats) => user_stats.option_ids.clone(),
+                None => return Vec::new(),
+            };
+            let now = Clock::current_time(TimePrecisionV2::Second);
+
+            option_ids
+                .into_iter()
+                .map(|id| {
+                    let contract: OptionContract = self.option_manager.get_non_fungible_data(&id);
+                    let spot = if contract.expiry <= now {
+                        self.get_mark_price_at(contract.underlying, contract.expiry)
+                    } else {
+                        *prices.get(&contract.underlying).expect("Price not found")
+                    };
+                    let value =
+                        contract.value(spot, self.get_option_volatility(contract.underlying), now);
+
+                    OptionPositionValue {
+                        id,
+                        contract,
+                        value,
+                    }
+                })
+                .collect()
+        }
+
+        /// Schedules a recurring trade of a fixed amount, e.g. 100 FUSD into ETH every 6 hours.
+        /// The first trade can be executed right away. The order runs until it is cancelled.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `from_address` - The resource address of the asset to be traded from.
+        /// * `to_address` - The resource address of the asset to be traded to.
+        /// * `amount` - The amount of the asset traded each interval.
+        /// * `interval` - The number of seconds between two trades.
+        ///
+        /// # Returns
+        ///
+        /// The ID of the order.
+        pub fn schedule_dca_order(
+            &mut self,
+            user_token_proof: Proof,
+            from_address: ResourceAddress,
+            to_address: ResourceAddress,
+            amount: Decimal,
+            interval: i64,
+        ) -> u64 {
+            let user_id = self.extract_user_id(user_token_proof);
+            let order = ScheduledOrder::dollar_cost_averaging(
+                user_id,
+                from_address,
+                to_address,
+                amount,
+                interval,
+                Clock::current_time(TimePrecisionV2::Second),
+            );
+
+            self.add_scheduled_order(order)
+        }
+
+        /// Schedules a large trade to be split into equal slices executed over time. The first
+        /// slice can be executed right away.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `from_address` - The resource address of the asset to be traded from.
+        /// * `to_address` - The resource address of the asset to be traded to.
+        /// * `total_amount` - The amount of the asset traded over all slices.
+        /// * `slices` - The number of slices.
+        /// * `interval` - The number of seconds between two slices.
+        ///
+        /// # Returns
+        ///
+        /// The ID of the order.
+        pub fn schedule_twap_order(
+            &mut self,
+            user_token_proof: Proof,
+            from_address: ResourceAddress,
+            to_address: ResourceAddress,
+            total_amount: Decimal,
+            slices: u64,
+            interval: i64,
+        ) -> u64 {
+            let user_id = self.extract_user_id(user_token_proof);
+            let order = ScheduledOrder::time_weighted_average_price(
+                user_id,
+                from_address,
+                to_address,
+                total_amount,
+                slices,
+                interval,
+                Clock::current_time(TimePrecisionV2::Second),
+            );
+
+            self.add_scheduled_order(order)
+        }
+
+        /// Stores a new scheduled order of a registered user.
+        ///
+        /// # Arguments
+        ///
+        /// * `order` - The order to store.
+        ///
+        /// # Returns
+        ///
+        /// The ID of the order.
+        fn add_scheduled_order(&mut self, order: ScheduledOrder) -> u64 {
+            self.assert_competition_running();
+            assert!(
+                self.trade_protection.reveal_delay.is_none(),
+                "Trades must be committed first in this competition."
+            );
+            self.get_reference_address(order.from_address);
+            self.get_reference_address(order.to_address);
+
+            let order_id = self.scheduled_order_count;
+            self.scheduled_order_count += 1;
+            {
+                let mut user_stats = self
+                    .user_stats
+                    .get_mut(&order.user_id)
+                    .expect("User is not registered.");
+                assert!(
+                    user_stats.order_ids.len() < MAX_OPEN_ORDERS,
+                    "A user can have at most {} open orders.",
+                    MAX_OPEN_ORDERS
+                );
+                user_stats.order_ids.push(order_id);
+            }
+            self.scheduled_orders.insert(order_id, order.clone());
+
+            Runtime::emit_event(OrderScheduledEvent { order_id, order });
+
+            order_id
+        }
+
+        /// Cancels a scheduled order. Slices that have already been executed are not reverted.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `order_id` - The ID of the order.
+        pub fn cancel_scheduled_order(&mut self, user_token_proof: Proof, order_id: u64) {
+            let user_id = self.extract_user_id(user_token_proof);
+            assert_eq!(
+                self.get_scheduled_order(order_id).user_id,
+                user_id,
+                "The order belongs to another user."
+            );
+
+            self.remove_scheduled_order(order_id);
+
+            Runtime::emit_event(ScheduledOrderCancelledEvent { order_id, user_id });
+        }
+
+        /// Executes the next slice of a scheduled order once it is due. The slice is bounded by
+        /// the user's balance and skipped if the balance is empty. Anyone can call this method.
+        ///
+        /// # Arguments
+        ///
+        /// * `order_id` - The ID of the order.
+        /// * `keeper_token_proof` - A proof of the keeper's user token, needed to receive the
+        ///   keeper reward.
+        ///
+        /// # Returns
+        ///
+        /// The keeper reward, empty if the slice was skipped or no reward is paid.
+        pub fn execute_scheduled_order(
+            &mut self,
+            order_id: u64,
+            keeper_token_proof: Option<Proof>,
+        ) -> Bucket {
+            self.assert_competition_running();
+
+            let mut order = self.get_scheduled_order(order_id);
+            assert!(
+                order.is_due(Clock::current_time(TimePrecisionV2::Second)),
+                "The next slice of the order is not due yet."
+            );
+
+            let balance = self
+                .user_asset_vaults
+                .get(&order.user_id)
+                .expect("User vault not found")
+                .get_balance(order.from_address);
+            let from_amount = order.next_slice_amount(balance);
+            if from_amount.is_positive() {
+                self.execute_trade(
+                    order.user_id.clone(),
+                    order.from_address,
+                    order.to_address,
+                    from_amount,
+                );
+            }
+
+            let completed = order.advance(Clock::current_time(TimePrecisionV2::Second));
+            if completed {
+                self.remove_scheduled_order(order_id);
+            } else {
+                self.scheduled_orders.insert(order_id, order.clone());
+            }
+
+            Runtime::emit_event(ScheduledOrderExecutedEvent {
+                order_id,
+                user_id: order.user_id.clone(),
+                from_amount,
+                completed,
+            });
+
+            if from_amount.is_positive()
+                && order.interval >= self.keeper_reward_config.min_rewarded_order_interval
+            {
+                let slice_value = from_amount * self.get_mark_price(order.from_address);
+                self.pay_keeper_reward(
+                    KeeperAction::ScheduledOrderSlice,
+                    keeper_token_proof,
+                    Some((&order.user_id, slice_value)),
+                )
+            } else {
+                Bucket::new(XRD)
+            }
+        }
+
+        /// Removes a scheduled order from the competition and its user's open orders.
+        ///
+        /// # Arguments
+        ///
+        /// * `order_id` - The ID of the order.
+        fn remove_scheduled_order(&mut self, order_id: u64) {
+            let order = self
+                .scheduled_orders
+                .remove(&order_id)
+                .expect("No open order with this ID");
+            self.user_stats
+                .get_mut(&order.user_id)
+                .expect("User stats not found")
+                .order_ids
+                .retain(|id| *id != order_id);
+        }
+
+        /// Gets an open scheduled order.
+        ///
+        /// # Arguments
+        ///
+        /// * `order_id` - The ID of the order.
+        ///
+        /// # Returns
+        ///
+        /// The order with the time and size of its next slice.
+        pub fn get_scheduled_order(&self, order_id: u64) -> ScheduledOrder {
+            self.scheduled_orders
+                .get(&order_id)
+                .expect("No open order with this ID")
+                .clone()
+        }
+
+        /// Gets the open scheduled orders of a user.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// The IDs and orders of the user, oldest first.
+        pub fn get_scheduled_orders(&self, user_id: String) -> Vec<(u64, ScheduledOrder)> {
+            let order_ids = match self.user_stats.get(&user_id) {
+                Some(user_stats) => user_stats.order_ids.clone(),
+                None => return Vec::new(),
+            };
+
+            order_ids
+                .into_iter()
+                .map(|order_id| (order_id, self.get_scheduled_order(order_id)))
+                .collect()
+        }
+
+        /// Follows a leader, so the leader's future trades are mirrored into the user's vault in
+        /// proportion to the portfolio sizes. A user follows at most one leader at a time.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `leader_id` - The ID of the user to follow.
+        /// * `settings` - The limits of the mirrored trades.
+        pub fn follow(
+            &mut self,
+            user_token_proof: Proof,
+            leader_id: String,
+            settings: CopySettings,
+        ) {
+            assert!(
+                !self.get_status().is_final(),
+                "Competition has already been settled or cancelled."
+            );
+            let user_id = self.extract_user_id(user_token_proof);
+            assert!(self.is_participant(&user_id), "User is not registered.");
+            assert!(
+                self.is_participant(&leader_id),
+                "The leader is not registered."
+            );
+            assert!(user_id != leader_id, "Users cannot follow themselves.");
+            assert!(
+                self.follows.get(&user_id).is_none(),
+                "User already follows a leader."
+            );
+            settings.assert_valid();
+
+            let (profit_share, next_copy_index) = {
+                let leader_stats = self
+                    .user_stats
+                    .get(&leader_id)
+                    .expect("User stats not found");
+                (leader_stats.profit_share, leader_stats.copy_trade_count)
+            };
+            assert!(
+                profit_share <= settings.max_profit_share,
+                "The leader's profit share of {} exceeds the accepted maximum.",
+                profit_share
+            );
+
+            self.follows.insert(
+                user_id.clone(),
+                Follow {
+                    leader_id: leader_id.clone(),
+                    settings,
+                    profit_share,
+                    next_copy_index,
+                },
+            );
+            let followers = self.followers.get_mut(&leader_id);
+            if let Some(mut followers) = followers {
+                followers.push(user_id.clone());
+            } else {
+                drop(followers);
+                self.followers
+                    .insert(leader_id.clone(), vec![user_id.clone()]);
+            }
+
+            Runtime::emit_event(LeaderFollowedEvent {
+                user_id,
+                leader_id,
+                profit_share,
+            });
+        }
+
+        /// Stops following the current leader. Trades of the leader that have not been mirrored
+        /// yet are dropped.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        pub fn unfollow(&mut self, user_token_proof: Proof) {
+            let user_id = self.extract_user_id(user_token_proof);
+            let follow = self
+                .follows
+                .remove(&user_id)
+                .expect("User does not follow a leader.");
+            self.followers
+                .get_mut(&follow.leader_id)
+                .expect("Followers not found")
+                .retain(|follower_id| *follower_id != user_id);
+
+            Runtime::emit_event(LeaderUnfollowedEvent {
+                user_id,
+                leader_id: follow.leader_id,
+            });
+        }
+
+        /// Sets the share of their followers' prizes a leader receives. The share only applies to
+        /// users who follow afterwards.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `profit_share` - The share of the prize, e.g. 0.1 for 10%.
+        pub fn set_profit_share(&mut self, user_token_proof: Proof, profit_share: Decimal) {
+            let user_id = self.extract_user_id(user_token_proof);
+            assert!(
+                !profit_share.is_negative() && profit_share <= MAX_PROFIT_SHARE,
+                "The profit share must be between 0 and {}.",
+                MAX_PROFIT_SHARE
+            );
+
+            self.user_stats
+                .get_mut(&user_id)
+                .expect("User is not registered.")
+                .profit_share = profit_share;
+        }
+
+        /// Mirrors the trades of a follower's leader that have not been mirrored yet, at most
+        /// `MAX_MIRRORED_TRADES_PER_CALL` per call. Anyone can call this method, it is needed for
+        /// leaders whose trades are not mirrored right away.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the follower.
+        /// * `keeper_token_proof` - A proof of the keeper's user token, needed to receive the
+        ///   keeper reward.
+        ///
+        /// # Returns
+        ///
+        /// The keeper reward, empty if no trade was mirrored or no reward is paid.
+        pub fn mirror_trades(
+            &mut self,
+            user_id: String,
+            keeper_token_proof: Option<Proof>,
+        ) -> Bucket {
+            self.assert_competition_running();
+            let follow = self
+                .follows
+                .get(&user_id)
+                .expect("User does not follow a leader.")
+                .clone();
+            let copy_trade_count = self
+                .user_stats
+                .get(&follow.leader_id)
+                .expect("User stats not found")
+                .copy_trade_count;
+            assert!(
+                follow.next_copy_index < copy_trade_count,
+                "There are no trades to mirror."
+            );
+
+            let mirrored_value = self.mirror_pending_trades(&user_id);
+            if mirrored_value.is_positive() {
+                self.pay_keeper_reward(
+                    KeeperAction::TradeMirroring,
+                    keeper_token_proof,
+                    Some((&user_id, mirrored_value)),
+                )
+            } else {
+                Bucket::new(XRD)
+            }
+        }
+
+        /// Stores a trade of a leader for mirroring and mirrors it right away if the leader has
+        /// few enough followers.
+        ///
+        /// # Arguments
+        ///
+        /// * `leader_id` - The ID of the leader who traded.
+        /// * `copy_trade` - The trade relative to the leader's portfolio.
+        fn publish_copy_trade(&mut self, leader_id: &str, copy_trade: CopyTrade) {
+            let copy_index = {
+                let mut leader_stats = self
+                    .user_stats
+                    .get_mut(&leader_id.to_string())
+                    .expect("User stats not found");
+                leader_stats.copy_trade_count += 1;
+                leader_stats.copy_trade_count - 1
+            };
+            self.copy_trades
+                .insert((leader_id.to_string(), copy_index), copy_trade);
+
+            let followers = self
+                .followers
+                .get(&leader_id.to_string())
+                .map(|followers| followers.clone())
+                .unwrap_or_default();
+            if followers.len() <= IMMEDIATE_MIRROR_LIMIT {
+                for follower_id in followers {
+                    self.mirror_pending_trades(&follower_id);
+                }
+            }
+        }
+
+        /// Mirrors the trades of a follower's leader that have not been mirrored yet, at most
+        /// `MAX_MIRRORED_TRADES_PER_CALL` of them. The follower's subscription keeps track of
+        /// the next trade to mirror.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the follower.
+        ///
+        /// # Returns
+        ///
+        /// The FUSD value of the trades that were mirrored. Trades below the limits of the
+        /// competition or the follower's balance are skipped.
+        fn mirror_pending_trades(&mut self, user_id: &str) -> Decimal {
+            let mut follow = match self.follows.get(&user_id.to_string()) {
+                Some(follow) => follow.clone(),
+                None => return Decimal::zero(),
+            };
+            let copy_trade_count = self
+                .user_stats
+                .get(&follow.leader_id)
+                .expect("User stats not found")
+                .copy_trade_count;
+
+            let batch_end = copy_trade_count.min(
+                follow
+                    .next_copy_index
+                    .saturating_add(MAX_MIRRORED_TRADES_PER_CALL),
+            );
+            let mut mirrored_value = Decimal::zero();
+            while follow.next_copy_index < batch_end {
+                let copy_index = follow.next_copy_index;
+                let copy_trade = self
+                    .copy_trades
+                    .get(&(follow.leader_id.clone(), copy_index))
+                    .expect("Copy trade not found")
+                    .clone();
+                follow.next_copy_index += 1;
+
+                if let Some(trade_value) = self.mirror_trade(user_id, &follow, &copy_trade) {
+                    mirrored_value += trade_value;
+                    Runtime::emit_event(TradeMirroredEvent {
+                        user_id: user_id.to_string(),
+                        leader_id: follow.leader_id.clone(),
+                        copy_index,
+                    });
+                }
+            }
+
+            // The leader earns their share on the value mirrored from their trades
+            if mirrored_value.is_positive() {
+                let mut user_stats = self
+                    .user_stats
+                    .get_mut(&user_id.to_string())
+                    .expect("User stats not found");
+                match user_stats
+                    .copied_leaders
+                    .iter_mut()
+                    .find(|(leader_id, profit_share, _)| {
+                        *leader_id == follow.leader_id && *profit_share == follow.profit_share
+                    }) {
+                    Some((_, _, value)) => *value += mirrored_value,
+                    None => user_stats.copied_leaders.push((
+                        follow.leader_id.clone(),
+                        follow.profit_share,
+                        mirrored_value,
+                    )),
+                }
+            }
+            self.follows.insert(user_id.to_string(), follow);
+
+            mirrored_value
+        }
+
+        /// Mirrors a single trade of a leader into a follower's vault. Mirrored trades are not
+        /// subject to the trade protections, as the follower does not choose their timing.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the follower.
+        /// * `follow` - The follower's subscription to the leader.
+        /// * `copy_trade` - The trade of the leader.
+        ///
+        /// # Returns
+        ///
+        /// The FUSD value of the mirrored trade, `None` if it was skipped because the follower
+        /// holds none of the asset or the mirrored trade would break the risk rules.
+        fn mirror_trade(
+            &mut self,
+            user_id: &str,
+            follow: &Follow,
+            copy_trade: &CopyTrade,
+        ) -> Option<Decimal> {
+            let prices = self.get_prices();
+            let portfolio = self.value_portfolio(user_id, prices.clone());
+            let from_price = *prices
+                .get(&copy_trade.from_address)
+                .expect("Price not found");
+
+            let balance = self
+                .user_asset_vaults
+                .get(&user_id.to_string())
+                .expect("User vault not found")
+                .get_balance(copy_trade.from_address);
+            let amount = follow
+                .mirrored_trade_value(copy_trade, portfolio.total_value)
+                .checked_div(from_price)
+                .expect("Division by zero error")
+                .min(balance);
+            let trade_value = amount * from_price;
+            if !amount.is_positive() || !self.risk_rules.allows_trade_value(trade_value) {
+                return None;
+            }
+            if copy_trade.to_address != self.fusd_resource_address {
+                let position_value = portfolio
+                    .positions
+                    .iter()
+                    .find(|position| position.resource_address == copy_trade.to_address)
+                    .map(|position| position.value)
+                    .unwrap_or_default();
+                if !self
+                    .risk_rules
+                    .allows_position_share(position_value + trade_value, portfolio.total_value)
+                {
+                    return None;
+                }
+            }
+
+            let trade = self.swap_assets(
+                user_id,
+                copy_trade.from_address,
+                copy_trade.to_address,
+                amount,
+            );
+            self.record_trade(user_id, trade);
+            Some(trade_value)
+        }
+
+        /// Checks whether any user follows a leader.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the leader.
+        fn has_followers(&self, user_id: &str) -> bool {
+            self.followers
+                .get(&user_id.to_string())
+                .is_some_and(|followers| !followers.is_empty())
+        }
+
+        /// Removes a user's subscription to a leader and the subscriptions of the user's
+        /// followers.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        fn remove_follows(&mut self, user_id: &str) {
+            let user_id = user_id.to_string();
+            if let Some(follow) = self.follows.remove(&user_id) {
+                self.followers
+                    .get_mut(&follow.leader_id)
+                    .expect("Followers not found")
+                    .retain(|follower_id| *follower_id != user_id);
+            }
+            if let Some(followers) = self.followers.remove(&user_id) {
+                for follower_id in followers {
+                    self.follows.remove(&follower_id);
+                }
+            }
+        }
+
+        /// Gets the leader a user follows.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the follower.
+        ///
+        /// # Returns
+        ///
+        /// The user's subscription, `None` if the user follows no one.
+        pub fn get_follow(&self, user_id: String) -> Option<Follow> {
+            self.follows.get(&user_id).map(|follow| follow.clone())
+        }
+
+        /// Gets the followers of a leader.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the leader.
+        ///
+        /// # Returns
+        ///
+        /// The IDs of the followers, in the order they followed.
+        pub fn get_followers(&self, user_id: String) -> Vec<String> {
+            self.followers
+                .get(&user_id)
+                .map(|followers| followers.clone())
+                .unwrap_or_default()
+        }
+
+        /// Gets the share of their followers' prizes a leader asks for.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the leader.
+        ///
+        /// # Returns
+        ///
+        /// The profit share for new followers.
+        pub fn get_profit_share(&self, user_id: String) -> Decimal {
+            self.user_stats
+                .get(&user_id)
+                .expect("User stats not found")
+                .profit_share
+        }
+
+        /// Stakes XRD on a player winning the competition. Only spectators who do not play in
+        /// the competition can bet, and only until betting closes.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        /// * `player_id` - The ID of the player expected to win.
+        /// * `stake` - A bucket of XRD.
+        pub fn place_bet(&mut self, user_token_proof: Proof, player_id: String, stake: Bucket) {
+            assert!(
+                !self.get_status().is_final(),
+                "Competition has already been settled or cancelled."
+            );
+            assert!(
+                Clock::current_time_is_strictly_before(
+                    self.get_betting_close_time(),
+                    TimePrecisionV2::Second
+                ),
+                "Betting has closed."
+            );
+            let user_id = self.extract_user_id(user_token_proof);
+            assert!(
+                !self.is_participant(&user_id),
+                "Players cannot bet on their own competition."
+            );
+            assert!(
+                self.is_participant(&player_id),
+                "The player is not registered."
+            );
+            assert!(
+                stake.resource_address() == XRD,
+                "Bets must be placed in XRD."
+            );
+            let amount = stake.amount();
+            assert!(amount.is_positive(), "The stake must be positive.");
+            self.bet_vault.put(stake);
+
+            let pool = self.bet_pools.get_mut(&player_id);
+            if let Some(mut pool) = pool {
+                *pool += amount;
+            } else {
+                drop(pool);
+                self.bet_pools.insert(player_id.clone(), amount);
+                self.bet_players.push(player_id.clone());
+            }
+
+            let bets = self.bets.get_mut(&user_id);
+            if let Some(mut bets) = bets {
+                match bets
+                    .iter_mut()
+                    .find(|(bet_player_id, _)| *bet_player_id == player_id)
+                {
+                    Some((_, bet_amount)) => *bet_amount += amount,
+                    None => bets.push((player_id.clone(), amount)),
+                }
+            } else {
+                drop(bets);
+                self.bets
+                    .insert(user_id.clone(), vec![(player_id.clone(), amount)]);
+            }
+
+            Runtime::emit_event(BetPlacedEvent {
+                user_id,
+                player_id,
+                amount,
+            });
+        }
+
+        /// Pays out a bettor after the competition was settled, or refunds the stakes after it
+        /// was cancelled or settled without a winner. Stakes on players who left the competition
+        /// are refunded.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        ///
+        /// # Returns
+        ///
+        /// A bucket containing the winnings and refunds in XRD.
+        pub fn claim_bet_winnings(&mut self, user_token_proof: Proof) -> Bucket {
+            let user_id = self.extract_user_id(user_token_proof);
+            let bets = self.bets.remove(&user_id).expect("No bets to claim.");
+
+            let amount = match (self.get_status(), self.betting_result.clone()) {
+                (CompetitionStatus::Cancelled, _) | (CompetitionStatus::Settled, None) => bets
+                    .iter()
+                    .fold(Decimal::zero(), |total, (_, stake)| total + *stake),
+                (CompetitionStatus::Settled, Some(betting_result)) => {
+                    let (valid_bets, voided_bets): (Vec<_>, Vec<_>) = bets
+                        .iter()
+                        .partition(|(player_id, _)| self.is_participant(player_id));
+                    let winning_stake = valid_bets
+                        .iter()
+                        .find(|(player_id, _)| *player_id == betting_result.winner_id)
+                        .map(|(_, stake)| *stake)
+                        .unwrap_or_default();
+                    let valid_stake = valid_bets
+                        .iter()
+                        .fold(Decimal::zero(), |total, (_, stake)| total + *stake);
+                    let refund = voided_bets
+                        .iter()
+                        .fold(Decimal::zero(), |total, (_, stake)| total + *stake);
+
+                    betting_result.payout(winning_stake, valid_stake) + refund
+                }
+                _ => panic!("Competition has not been settled or cancelled yet."),
+            };
+
+            Runtime::emit_event(BetWinningsClaimedEvent { user_id, amount });
+
+            self.bet_vault.take(amount)
+        }
+
+        /// Sums up the bets of a settled competition.
+        ///
+        /// # Arguments
+        ///
+        /// * `winner_id` - The ID of the winner.
+        ///
+        /// # Returns
+        ///
+        /// The stakes on the winner and on all players still in the competition.
+        fn calculate_betting_result(&self, winner_id: &str) -> BettingResult {
+            let payout_pool = self
+                .bet_players
+                .iter()
+                .filter(|player_id| self.is_participant(player_id))
+                .fold(Decimal::zero(), |total, player_id| {
+                    total + *self.bet_pools.get(player_id).expect("Bet pool not found")
+                });
+            let winning_pool = self
+                .bet_pools
+                .get(&winner_id.to_string())
+                .map(|pool| *pool)
+                .unwrap_or_default();
+
+            BettingResult {
+                winner_id: winner_id.to_string(),
+                winning_pool,
+                payout_pool,
+            }
+        }
+
+        /// Gets the time until which spectators can bet.
+        ///
+        /// # Returns
+        ///
+        /// The betting close time set by the admin, otherwise the competition start.
+        pub fn get_betting_close_time(&self) -> Instant {
+            self.betting_close
+                .unwrap_or(self.competition_data.competition_start)
+        }
+
+        /// Gets the XRD staked on each player.
+        ///
+        /// # Returns
+        ///
+        /// The player IDs and their bet pools, in the order of the first bet.
+        pub fn get_bet_pools(&self) -> Vec<(String, Decimal)> {
+            self.bet_players
+                .iter()
+                .map(|player_id| {
+                    (
+                        player_id.clone(),
+                        *self.bet_pools.get(player_id).expect("Bet pool not found"),
+                    )
+                })
+                .collect()
+        }
+
+        /// Gets the unclaimed bets of a spectator.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the bettor.
+        ///
+        /// # Returns
+        ///
+        /// The player IDs and the XRD staked on them.
+        pub fn get_bets(&self, user_id: String) -> Vec<(String, Decimal)> {
+            self.bets
+                .get(&user_id)
+                .map(|bets| bets.clone())
+                .unwrap_or_default()
+        }
+
+        /// Gets the outcome of the spectator bets.
+        ///
+        /// # Returns
+        ///
+        /// The winner and the bet pools, `None` until the competition is settled.
+        pub fn get_betting_result(&self) -> Option<BettingResult> {
+            self.betting_result.clone()
+        }
+
+        /// Gets the rewards referrers earn for each referred player.
+        pub fn get_referral_config(&self) -> ReferralConfig {
+            self.referral_config.clone()
+        }
+
+        /// Gets the user who referred a user.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the referred user.
+        pub fn get_referrer(&self, user_id: String) -> Option<String> {
+            self.referrers
+                .get(&user_id)
+                .map(|referrer_id| referrer_id.clone())
+        }
+
+        /// Gets what each referrer has earned.
+        ///
+        /// # Returns
+        ///
+        /// The referrer IDs and their stats, in the order of their first referral.
+        pub fn get_referrer_stats(&self) -> Vec<(String, ReferrerStats)> {
+            self.referrer_ids
+                .iter()
+                .map(|referrer_id| {
+                    (
+                        referrer_id.clone(),
+                        self.referrer_stats
+                            .get(referrer_id)
+                            .expect("Referrer stats not found")
+                            .clone(),
+                    )
+                })
+                .collect()
+        }
+
+        /// Gets the season the competition reports its results to.
+        pub fn get_season(&self) -> Option<ComponentAddress> {
+            self.season.as_ref().map(|(season, _)| season.address())
+        }
+
+        /// Checks a trade against the rules of the competition, swaps the assets in the user's
+        /// vault and records the trade.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user who trades.
+        /// * `from_address` - The resource address of the asset to be traded from.
+        /// * `to_address` - The resource address of the asset to be traded to.
+        /// * `amount` - The amount of the asset to be traded.
+        fn execute_trade(
+            &mut self,
+            user_id: String,
+            from_address: ResourceAddress,
+            to_address: ResourceAddress,
+            amount: Decimal,
+        ) {
+            info!(
+                "I want to trade {:?} of {:?} into {:?}",
+                amount, from_address, to_address
+            );
+
+            // Check the trade against the risk rules, valuing assets only if a rule or the user's
+            // followers need prices
+            let has_followers = self.has_followers(&user_id);
+            let prices = (self.risk_rules.min_trade_value.is_some()
+                || self.risk_rules.max_position_share.is_some()
+                || has_followers)
+                .then(|| self.get_prices());
+            if let Some(prices) = &prices {
+                let from_price = *prices
+                    .get(&from_address)
+                    .expect("Asset is not traded in this competition");
+                self.risk_rules.assert_trade_value(amount * from_price);
+            }
+            self.assert_trade_timing(&user_id, from_address, to_address);
+            self.count_daily_trade(&user_id);
+
+            // Express the trade relative to the portfolio so followers of any size can mirror it
+            let copy_trade = match &prices {
+                Some(prices) if has_followers => {
+                    let portfolio_value =
+                        self.value_portfolio(&user_id, prices.clone()).total_value;
+                    Some(CopyTrade {
+                        from_address,
+                        to_address,
+                        portfolio_share: (amount
+                            * *prices.get(&from_address).expect("Price not found"))
+                        .checked_div(portfolio_value)
+                        .expect("Division by zero error"),
+                    })
+                }
+                _ => None,
+            };
+
+            let trade = self.swap_assets(&user_id, from_address, to_address, amount);
+
+            if let Some(prices) = prices {
+                if to_address != self.fusd_resource_address {
+                    self.assert_position_within_limit(&user_id, to_address, prices);
+                }
+            }
+
+            self.record_trade(&user_id, trade);
+
+            if let Some(copy_trade) = copy_trade {
+                self.publish_copy_trade(&user_id, copy_trade);
+            }
+        }
+
+        /// Swaps assets in a user's vault at the prices of the trade simulator.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user who trades.
+        /// * `from_address` - The resource address of the asset to be traded from.
+        /// * `to_address` - The resource address of the asset to be traded to.
+        /// * `amount` - The amount of the asset to be traded.
+        ///
+        /// # Returns
+        ///
+        /// The record of the executed trade.
+        fn swap_assets(
+            &self,
+            user_id: &str,
+            from_address: ResourceAddress,
+            to_address: ResourceAddress,
+            amount: Decimal,
+        ) -> TradeRecord {
+            // Price the swap by the reference assets
+            let oracle: Global<SimpleOracle> = self.trade_simulator.get_oracle_address().into();
+            let from_price = oracle.get_price(self.get_reference_address(from_address));
+            let to_price = oracle.get_price(self.get_reference_address(to_address));
+            let price_ratio = from_price
+                .checked_div(to_price)
+                .expect("Division by zero error");
+            let to_amount = amount
+                .checked_mul(price_ratio)
+                .expect("Multiplication overflow")
+                .checked_round(DIVISIBILITY_MAXIMUM, RoundingMode::ToZero)
+                .expect("Rounding error");
+
+            // Withdraw asset from the user vault
+            let user_asset_vault = self
+                .user_asset_vaults
+                .get(&user_id.to_string())
+                .expect("User vault not found");
+            let from_token_bucket = user_asset_vault.withdraw_asset(from_address, amount);
+
+            // Swap asset
+            from_token_bucket.burn();
+            let to_token_bucket = FungibleResourceManager::from(to_address).mint(to_amount);
+
+            // Deposit new assets back to the user vault
+            user_asset_vault.deposit_asset(to_token_bucket.into());
+
+            TradeRecord {
+                timestamp: Clock::current_time(TimePrecisionV2::Second),
+                from_address,
+                to_address,
+                from_amount: amount,
+                to_amount,
+                from_price,
+                to_price,
+                fee: Decimal::zero(), // The simulator charges no trading fee.
+            }
+        }
+
+        /// Asserts that the trade protections allow a user to trade two assets now.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user who trades.
+        /// * `from_address` - The resource address of the asset to be traded from.
+        /// * `to_address` - The resource address of the asset to be traded to.
+        fn assert_trade_timing(
+            &self,
+            user_id: &str,
+            from_address: ResourceAddress,
+            to_address: ResourceAddress,
+        ) {
+            let now = Clock::current_time(TimePrecisionV2::Second);
+            let last_trade = self
+                .user_stats
+                .get(&user_id.to_string())
+                .expect("User stats not found")
+                .last_trade;
+            self.trade_protection
+                .assert_cooldown_passed(last_trade, now);
+
+            if self.trade_protection.min_price_age.is_some() {
+                let oracle: Global<SimpleOracle> = self.trade_simulator.get_oracle_address().into();
+                for resource_address in [from_address, to_address, self.fusd_resource_address] {
+                    let last_update =
+                        oracle.get_last_update(self.get_reference_address(resource_address));
+                    self.trade_protection.assert_price_settled(last_update, now);
+                }
+            }
+        }
+
+        /// Withdraws all achievement badges a user has earned but not yet claimed.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        ///
+        /// # Returns
+        ///
+        /// A bucket containing the user's achievement badges.
+        pub fn claim_achievements(&mut self, user_token_proof: Proof) -> Bucket {
+            let user_id = self.extract_user_id(user_token_proof);
+
+            match self.unclaimed_achievements.get_mut(&user_id) {
+                Some(mut vault) => vault.take_all().into(),
+                None => self.achievement_badge_manager.create_empty_bucket().into(),
+            }
+        }
+
+        /// Counts a trade towards the user's trades of the current trading day.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user who trades.
+        fn count_daily_trade(&mut self, user_id: &str) {
+            let trading_day = self.current_trading_day();
+
+            let mut user_stats = self
+                .user_stats
+                .get_mut(&user_id.to_string())
+                .expect("User stats not found");
+            if user_stats.trading_day != trading_day {
+                user_stats.trading_day = trading_day;
+                user_stats.trades_on_trading_day = 0;
+            }
+            user_stats.trades_on_trading_day += 1;
+            self.risk_rules
+                .assert_trade_count(user_stats.trades_on_trading_day);
+        }
+
+        /// Gets the number of the current trading day, counted from the competition start.
+        ///
+        /// # Returns
+        ///
+        /// The current trading day, starting at 0.
+        fn current_trading_day(&self) -> i64 {
+            let now = Clock::current_time(TimePrecisionV2::Second);
+            (now.seconds_since_unix_epoch
+                - self
+                    .competition_data
+                    .competition_start
+                    .seconds_since_unix_epoch)
+                / SECONDS_PER_TRADING_DAY
+        }
+
+        /// Asserts that a user's position in an asset does not exceed the maximum position share.
+        /// The position includes the leveraged notional of an open perpetual position and the
+        /// value of options on the asset.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        /// * `resource_address` - The resource address of the asset.
+        /// * `prices` - The FUSD price of every virtual asset.
+        fn assert_position_within_limit(
+            &self,
+            user_id: &str,
+            resource_address: ResourceAddress,
+            prices: IndexMap<ResourceAddress, Decimal>,
+        ) {
+            let perpetual_notional = self
+                .perpetual_positions
+                .get(&(user_id.to_string(), resource_address))
+                .map(|position| {
+                    position.size * *prices.get(&resource_address).expect("Price not found")
+                })
+                .unwrap_or_default();
+            let option_value = self
+                .value_option_positions(user_id, &prices)
+                .iter()
+                .filter(|option| option.contract.underlying == resource_address)
+                .fold(Decimal::zero(), |total, option| total + option.value);
+            let portfolio = self.value_portfolio(user_id, prices);
+            let position_value = portfolio
+                .positions
+                .iter()
+                .find(|position| position.resource_address == resource_address)
+                .map(|position| position.value)
+                .unwrap_or_default()
+                + perpetual_notional
+                + option_value;
+            self.risk_rules
+                .assert_position_share(position_value, portfolio.total_value);
+        }
+
+        /// Records the time of a user's trade, from which the trade cooldown is measured.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user who traded.
+        fn record_trade_time(&mut self, user_id: &str) {
+            self.user_stats
+                .get_mut(&user_id.to_string())
+                .expect("User stats not found")
+                .last_trade = Some(Clock::current_time(TimePrecisionV2::Second));
+        }
+
+        /// Appends a trade to the user's trade history and awards trade related achievements.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user who traded.
+        /// * `trade` - The executed trade.
+        fn record_trade(&mut self, user_id: &str, trade: TradeRecord) {
+            let trade_count = {
+                let mut user_stats = self
+                    .user_stats
+                    .get_mut(&user_id.to_string())
+                    .expect("User stats not found");
+                user_stats.trade_count += 1;
+                user_stats.last_trade = Some(trade.timestamp);
+                user_stats.trade_count
+            };
+            let trade_index = trade_count - 1;
+
+            self.trade_history
+                .insert((user_id.to_string(), trade_index), trade.clone());
+
+            Runtime::emit_event(TradeExecutedEvent {
+                user_id: user_id.to_string(),
+                trade_index,
+                trade,
+            });
+
+            if trade_count == 1 {
+                self.award_achievement(user_id, Achievement::FirstTrade);
+            }
+            if trade_count == HUNDRED_TRADES_MILESTONE {
+                self.award_achievement(user_id, Achievement::HundredTrades);
+            }
+
+            let portfolio_value = self.get_scored_value(user_id);
+            self.check_return_achievement(user_id, portfolio_value);
+        }
+
+        /// Gets the FUSD value of a user's portfolio that counts towards the score. Referral
+        /// bonuses are left out, so that players cannot raise their score by referring
+        /// accounts of their own.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// The portfolio value minus the referral bonuses the user received.
+        fn get_scored_value(&self, user_id: &str) -> Decimal {
+            let referral_bonus = self
+                .user_stats
+                .get(&user_id.to_string())
+                .expect("User stats not found")
+                .referral_bonus;
+            self.get_user_portfolio(user_id.to_string()).total_value - referral_bonus
+        }
+
+        /// Awards the `TenXReturn` achievement if the portfolio value reached the milestone.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        /// * `portfolio_value` - The current FUSD value of the user's portfolio, without referral
+        ///   bonuses.
+        fn check_return_achievement(&mut self, user_id: &str, portfolio_value: Decimal) {
+            let starting_balance = self
+                .user_stats
+                .get(&user_id.to_string())
+                .expect("User stats not found")
+                .starting_balance;
+            if portfolio_value >= starting_balance * TEN_X_RETURN_MULTIPLE {
+                self.award_achievement(user_id, Achievement::TenXReturn);
+            }
+        }
+
+        /// Records the FUSD value of the next participants' portfolios.
+        ///
+        /// Anyone can take a snapshot while the competition is running, but at most once per
+        /// snapshot interval. Large competitions are processed over several calls, and the
+        /// snapshot only counts once every participant is valued.
+        ///
+        /// # Arguments
+        ///
+        /// * `batch_size` - The maximum number of portfolios to value in this call.
+        /// * `keeper_token_proof` - A proof of the keeper's user token, needed to receive the
+        ///   keeper reward.
+        ///
+        /// # Returns
+        ///
+        /// The number of participants still to be valued, and the keeper reward, which is only
+        /// paid for the call completing the snapshot.
+        pub fn take_snapshot(
+            &mut self,
+            batch_size: u64,
+            keeper_token_proof: Option<Proof>,
+        ) -> (u64, Bucket) {
+            self.assert_competition_running();
+
+            let (timestamp, valued_count) = match self.pending_snapshot {
+                Some(pending_snapshot) => pending_snapshot,
+                None => {
+                    if let Some(last_snapshot) = self.last_snapshot {
+                        let next_snapshot = last_snapshot
+                            .add_seconds(self.snapshot_interval)
+                            .expect("Time overflow");
+                        assert!(
+                            Clock::current_time_is_at_or_after(
+                                next_snapshot,
+                                TimePrecisionV2::Second
+                            ),
+                            "The snapshot interval has not passed yet."
+                        );
+                    }
+                    (Clock::current_time(TimePrecisionV2::Second), 0)
+                }
+            };
+
+            let batch_end = valued_count
+                .saturating_add(batch_size)
+                .min(self.participant_count);
+            for index in valued_count..batch_end {
+                let user_id = self
+                    .participants
+                    .get(&index)
+                    .expect("Participant not found")
+                    .clone();
+                let value = self.get_scored_value(&user_id);
+                self.equity_curves
+                    .get_mut(&user_id)
+                    .expect("Equity curve not found")
+                    .push(EquityPoint { timestamp, value });
+                self.check_return_achievement(&user_id, value);
+            }
+
+            let remaining = self.participant_count - batch_end;
+            if remaining > 0 {
+                self.pending_snapshot = Some((timestamp, batch_end));
+                return (remaining, Bucket::new(XRD));
+            }
+            self.pending_snapshot = None;
+            self.last_snapshot = Some(timestamp);
+
+            Runtime::emit_event(SnapshotTakenEvent {
+                timestamp,
+                participant_count: self.participant_count,
+            });
+
+            (
+                0,
+                self.pay_keeper_reward(KeeperAction::Snapshot, keeper_token_proof, None),
+            )
+        }
+
+        /// Adds XRD to the vault keepers are rewarded from. Anyone can fund the rewards.
+        ///
+        /// # Arguments
+        ///
+        /// * `xrd` - A bucket of XRD.
+        pub fn fund_keeper_rewards(&mut self, xrd: Bucket) {
+            assert!(
+                xrd.resource_address() == XRD,
+                "Keeper rewards must be funded with XRD."
+            );
+
+            Runtime::emit_event(KeeperRewardsFundedEvent {
+                amount: xrd.amount(),
+            });
+            self.keeper_rewards.put(xrd);
+        }
+
+        /// Takes the keeper reward for an executed action from the reward vault. Nothing is paid
+        /// once the daily limit is reached, and less than the reward once the vault runs low.
+        /// Actions on behalf of a user are only rewarded if they are worth the minimum rewarded
+        /// value and the keeper is someone else, so players cannot farm rewards on themselves.
+        ///
+        /// # Arguments
+        ///
+        /// * `action` - The action the keeper executed.
+        /// * `keeper_token_proof` - A proof of the keeper's user token, nothing is paid without.
+        /// * `user_action` - The ID of the user the action was executed for and the FUSD value of
+        ///   the action, `None` for actions on the whole competition.
+        ///
+        /// # Returns
+        ///
+        /// A bucket containing the reward.
+        fn pay_keeper_reward(
+            &mut self,
+            action: KeeperAction,
+            keeper_token_proof: Option<Proof>,
+            user_action: Option<(&str, Decimal)>,
+        ) -> Bucket {
+            let Some(keeper_token_proof) = keeper_token_proof else {
+                return Bucket::new(XRD);
+            };
+            let keeper_id = self.extract_user_id(keeper_token_proof);
+            if let Some((user_id, action_value)) = user_action {
+                if keeper_id == user_id
+                    || !self.keeper_reward_config.rewards_action_value(action_value)
+                {
+                    return Bucket::new(XRD);
+                }
+            }
+
+            let trading_day = self.current_trading_day();
+            if self.keeper_reward_day != trading_day {
+                self.keeper_reward_day = trading_day;
+                self.keeper_rewards_on_day = 0;
+            }
+            if !self
+                .keeper_reward_config
+                .allows_reward(self.keeper_rewards_on_day)
+            {
+                return Bucket::new(XRD);
+            }
+
+            let amount = self
+                .keeper_reward_config
+                .reward
+                .min(self.keeper_rewards.amount());
+            if !amount.is_positive() {
+                return Bucket::new(XRD);
+            }
+            self.keeper_rewards_on_day += 1;
+
+            Runtime::emit_event(KeeperRewardPaidEvent { action, amount });
+
+            self.keeper_rewards.take(amount)
+        }
+
+        /// Mints an achievement badge for a user unless it has been awarded before.
+        ///
+        /// The badge is kept by the competition until the user claims it.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user who reached the milestone.
+        /// * `achievement` - The milestone that has been reached.
+        fn award_achievement(&mut self, user_id: &str, achievement: Achievement) {
+            let user_id = user_id.to_string();
+            {
+                let mut user_stats = self
+                    .user_stats
+                    .get_mut(&user_id)
+                    .expect("User stats not found");
+                if user_stats.achievements.contains(&achievement) {
+                    return;
+                }
+                user_stats.achievements.push(achievement);
+            }
+
+            let config = self
+                .achievement_configs
+                .get(&achievement)
+                .map(|config| config.clone())
+                .unwrap_or_else(|| achievement.default_config());
+
+            let badge = self
+                .achievement_badge_manager
+                .mint_ruid_non_fungible(AchievementBadge {
+                    name: config.name,
+                    description: config.description,
+                    key_image_url: Url::of(config.key_image_url),
+                    achievement,
+                    user_id: user_id.clone(),
+                    competition: Runtime::global_address(),
+                    awarded_at: Clock::current_time(TimePrecisionV2::Second),
+                });
+
+            Runtime::emit_event(AchievementAwardedEvent {
+                user_id: user_id.clone(),
+                achievement,
+                badge_id: badge.non_fungible_local_id(),
+            });
+
+            let entry = self.unclaimed_achievements.get_mut(&user_id);
+            if let Some(mut vault) = entry {
+                vault.put(badge);
+            } else {
+                drop(entry);
+                self.unclaimed_achievements
+                    .insert(user_id, NonFungibleVault::with_bucket(badge));
+            }
+        }
+
+        /// Calculates the FUSD a user receives when registering now.
+        ///
+        /// # Returns
+        ///
+        /// The starting balance, reduced by the late registration policy if the competition
+        /// has already started.
+        fn calculate_starting_balance(&self) -> Decimal {
+            let now = Clock::current_time(TimePrecisionV2::Second);
+            if now.compare(
+                self.competition_data.competition_start,
+                TimeComparisonOperator::Lt,
+            ) {
+                return STARTING_BALANCE;
+            }
+
+            match self.late_registration_policy {
+                LateRegistrationPolicy::FullBalance => STARTING_BALANCE,
+                LateRegistrationPolicy::ProRated => {
+                    let duration = self
+                        .competition_data
+                        .competition_end
+                        .seconds_since_unix_epoch
+                        - self
+                            .competition_data
+                            .competition_start
+                            .seconds_since_unix_epoch;
+                    let remaining = self
+                        .competition_data
+                        .competition_end
+                        .seconds_since_unix_epoch
+                        - now.seconds_since_unix_epoch;
+                    (STARTING_BALANCE * Decimal::from(remaining))
+                        .checked_div(Decimal::from(duration))
+                        .expect("Division by zero error")
+                }
+                LateRegistrationPolicy::Penalized(penalty) => {
+                    STARTING_BALANCE * (Decimal::one() - penalty)
+                }
+            }
+        }
+
+        /// Asserts that the registration window is open.
+        fn assert_registration_open(&self) {
+            assert!(
+                self.get_status() != CompetitionStatus::Cancelled,
+                "Competition has been cancelled."
+            );
+            assert!(
+                Clock::current_time_is_at_or_after(
+                    self.competition_data.registration_start,
+                    TimePrecisionV2::Second,
+                ),
+                "Registration has not opened yet!"
+            );
+            assert!(
+                Clock::current_time_is_strictly_before(
+                    self.competition_data.registration_end,
+                    TimePrecisionV2::Second,
+                ),
+                "Registration is closed!"
+            );
+            assert!(
+                Clock::current_time_is_strictly_before(
+                    self.competition_data.competition_end,
+                    TimePrecisionV2::Second,
+                ),
+                "Competition has already finished."
+            );
+        }
+
+        /// Asserts that the competition has not started yet.
+        fn assert_competition_not_started(&self) {
+            info!(
+                "Current time: {:?}",
+                Clock::current_time(TimePrecisionV2::Second)
+            );
+            info!(
+                "Current competition start: {:?}",
+                self.competition_data.competition_start
+            );
+            match self.get_status() {
+                CompetitionStatus::Created | CompetitionStatus::RegistrationOpen => {}
+                CompetitionStatus::Cancelled => panic!("Competition has been cancelled."),
+                _ => panic!("Competition has already started."),
+            }
+        }
+
+        /// Asserts that the competition is currently running.
+        fn assert_competition_running(&self) {
+            match self.get_status() {
+                CompetitionStatus::Running => {}
+                CompetitionStatus::Created | CompetitionStatus::RegistrationOpen => {
+                    panic!("Competition has not started yet!")
+                }
+                CompetitionStatus::Cancelled => panic!("Competition has been cancelled."),
+                _ => panic!("Competition has already finished."),
+            }
+        }
+
+        /// Moves the competition to a final status.
+        ///
+        /// # Arguments
+        ///
+        /// * `next` - The status to move to.
+        fn transition_to(&mut self, next: CompetitionStatus) {
+            let current = self.get_status();
+            assert!(
+                current.can_transition_to(next),
+                "The competition cannot move from {:?} to {:?}.",
+                current,
+                next
+            );
+            self.status = next;
+        }
+
+        /// Puts tokens into the prize pool of their resource.
+        ///
+        /// # Arguments
+        ///
+        /// * `tokens` - The tokens to add to the prize pool.
+        fn deposit_to_prize_pool(&mut self, tokens: Bucket) {
+            let resource_address = tokens.resource_address();
+            let prize_pool = self.prize_pools.get_mut(&resource_address);
+            if let Some(mut prize_pool) = prize_pool {
+                prize_pool.put(tokens);
+            } else {
+                drop(prize_pool);
+                self.prize_pools
+                    .insert(resource_address, Vault::with_bucket(tokens));
+                self.prize_resource_addresses.push(resource_address);
+            }
+        }
+
+        /// Adds a sponsor's tokens to the prize pools. The payout table splits them among the
+        /// winners like the entry fees. Only the resources the admin allowed for the sponsor can
+        /// be deposited, and the prize pools hold at most `MAX_SPONSORED_RESOURCES` resources
+        /// besides the entry fee resource.
+        ///
+        /// # Arguments
+        ///
+        /// * `sponsor_id` - The ID of the sponsor the tokens are deposited for.
+        /// * `tokens` - The sponsored tokens.
+        pub fn sponsor_prize_pool(&mut self, sponsor_id: u64, tokens: Bucket) {
+            assert!(
+                !self.is_sponsor_pool_locked(),
+                "The sponsor prize pool is locked."
+            );
+            let resource_address = tokens.resource_address();
+            assert!(
+                tokens.resource_manager().resource_type().is_fungible(),
+                "Prizes must be fungible."
+            );
+            let amount = tokens.amount();
+            assert!(amount.is_positive(), "The deposit is empty.");
+            if self.prize_pools.get(&resource_address).is_none() {
+                let sponsored_resource_count = self
+                    .prize_resource_addresses
+                    .iter()
+                    .filter(|address| **address != self.entry_fee_resource_address)
+                    .count();
+                assert!(
+                    sponsored_resource_count < MAX_SPONSORED_RESOURCES,
+                    "The prize pools already hold {} sponsored resources.",
+                    MAX_SPONSORED_RESOURCES
+                );
+            }
+
+            {
+                let mut sponsor = self
+                    .sponsors
+                    .get_mut(&sponsor_id)
+                    .expect("Sponsor not found");
+                sponsor.assert_accepts(resource_address);
+                sponsor.add_contribution(resource_address, amount);
+            }
+            self.deposit_to_prize_pool(tokens);
+
+            Runtime::emit_event(PrizePoolSponsoredEvent {
+                sponsor_id,
+                resource_address,
+                amount,
+            });
+        }
+
+        /// Extracts the user ID from the provided proof.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        ///
+        /// # Returns
+        ///
+        /// A string representing the user ID.
+        fn extract_user_id(&self, user_token_proof: Proof) -> String {
+            let checked_proof = user_token_proof.check(self.user_token_resource_address);
+            checked_proof
+                .as_non_fungible()
+                .non_fungible_local_id()
+                .to_string()
+        }
+
+        /// Sets the time from which users can register.
+        ///
+        /// # Arguments
+        ///
+        /// * `time` - The new registration start time.
+        pub fn set_registration_start_time(&mut self, time: Instant) {
+            self.assert_competition_not_started();
+            self.competition_data.registration_start = time;
+            self.competition_data.assert_valid();
+            self.emit_competition_time_changed();
+        }
+
+        /// Sets the time until which users can register.
+        ///
+        /// # Arguments
+        ///
+        /// * `time` - The new registration end time.
+        pub fn set_registration_end_time(&mut self, time: Instant) {
+            self.assert_competition_not_started();
+            self.competition_data.registration_end = time;
+            self.competition_data.assert_valid();
+            self.emit_competition_time_changed();
+        }
+
+        /// Sets the starting balance of users who register after the competition started.
+        ///
+        /// # Arguments
+        ///
+        /// * `policy` - The new late registration policy.
+        pub fn set_late_registration_policy(&mut self, policy: LateRegistrationPolicy) {
+            if let LateRegistrationPolicy::Penalized(penalty) = policy {
+                assert!(
+                    penalty >= Decimal::zero() && penalty < Decimal::one(),
+                    "The penalty must be at least 0 and below 1."
+                );
+            }
+            self.late_registration_policy = policy;
+        }
+
+        /// Limits the number of users who can register.
+        ///
+        /// # Arguments
+        ///
+        /// * `max_participants` - The maximum number of participants, or `None` for no limit.
+        pub fn set_max_participants(&mut self, max_participants: Option<u64>) {
+            if let Some(max_participants) = max_participants {
+                assert!(
+                    max_participants >= self.participant_count,
+                    "More users have already registered."
+                );
+            }
+            self.max_participants = max_participants;
+        }
+
+        /// Sets who is allowed to register for the competition.
+        ///
+        /// # Arguments
+        ///
+        /// * `registration_access` - The new registration access.
+        pub fn set_registration_access(&mut self, registration_access: RegistrationAccess) {
+            self.registration_access = registration_access;
+        }
+
+        /// Adds users to the allowlist.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_ids` - The IDs of the users to allow.
+        pub fn add_to_allowlist(&mut self, user_ids: Vec<String>) {
+            for user_id in user_ids {
+                self.allowlist.insert(user_id, ());
+            }
+        }
+
+        /// Removes users from the allowlist. Users who already registered stay registered.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_ids` - The IDs of the users to remove.
+        pub fn remove_from_allowlist(&mut self, user_ids: Vec<String>) {
+            for user_id in user_ids {
+                self.allowlist.remove(&user_id);
+            }
+        }
+
+        /// Adds invite codes. Only the hashes are stored, so the codes stay secret until used.
+        ///
+        /// # Arguments
+        ///
+        /// * `invite_code_hashes` - The Blake2b hashes of the invite codes.
+        pub fn add_invite_codes(&mut self, invite_code_hashes: Vec<Hash>) {
+            for invite_code_hash in invite_code_hashes {
+                self.invite_code_hashes.insert(invite_code_hash, ());
+            }
+        }
+
+        /// Sets the competition start time. Only possible before the competition starts.
+        ///
+        /// # Arguments
+        ///
+        /// * `time` - The new start time of the competition.
+        pub fn set_competition_start_time(&mut self, time: Instant) {
+            self.assert_competition_not_started();
+            assert!(
+                Clock::current_time_is_strictly_before(time, TimePrecisionV2::Second),
+                "The competition start must be in the future."
+            );
+            self.competition_data.competition_start = time;
+            self.competition_data.assert_valid();
+            self.emit_competition_time_changed();
+        }
+
+        /// Sets the competition end time. Only possible before the competition starts.
+        ///
+        /// # Arguments
+        ///
+        /// * `time` - The new end time of the competition.
+        pub fn set_competition_end_time(&mut self, time: Instant) {
+            self.assert_competition_not_started();
+            self.competition_data.competition_end = time;
+            self.competition_data.assert_valid();
+            self.emit_competition_time_changed();
+        }
+
+        /// Sets the entry fee users pay at registration. The fees go into the prize pool.
+        ///
+        /// Only possible before anyone has registered.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The resource the entry fee is paid in.
+        /// * `amount` - The entry fee, zero for a free competition.
+        pub fn set_entry_fee(&mut self, resource_address: ResourceAddress, amount: Decimal) {
+            assert!(
+                self.participant_count == 0,
+                "The entry fee cannot change after users registered."
+            );
+            assert!(!amount.is_negative(), "The entry fee cannot be negative.");
+            self.entry_fee_resource_address = resource_address;
+            self.entry_fee = amount;
+        }
+
+        /// Sets whether prizes unlock over time after the competition ends, e.g. 20% at the end
+        /// and the rest linearly over 30 days. Only possible before the competition starts.
+        ///
+        /// # Arguments
+        ///
+        /// * `prize_vesting` - The vesting schedule, `None` to pay prizes at once.
+        pub fn set_prize_vesting(&mut self, prize_vesting: Option<VestingSchedule>) {
+            self.assert_competition_not_started();
+            if let Some(schedule) = prize_vesting {
+                schedule.assert_valid();
+            }
+            self.prize_vesting = prize_vesting;
+        }
+
+        /// Adds a partner who can fund the prize pools.
+        ///
+        /// # Arguments
+        ///
+        /// * `info` - The name, logo and website of the sponsor shown by the frontend.
+        /// * `prize_resources` - The fungible resources the sponsor may deposit.
+        ///
+        /// # Returns
+        ///
+        /// The ID of the sponsor, used to deposit for them.
+        pub fn add_sponsor(
+            &mut self,
+            info: SponsorInfo,
+            prize_resources: Vec<ResourceAddress>,
+        ) -> u64 {
+            let sponsor_id = self.sponsor_count;
+            Runtime::emit_event(SponsorAddedEvent {
+                sponsor_id,
+                name: info.name.clone(),
+            });
+            self.sponsors
+                .insert(sponsor_id, Sponsor::new(info, prize_resources));
+            self.sponsor_count += 1;
+            sponsor_id
+        }
+
+        /// Sets whether sponsors can only deposit before the competition starts, so players know
+        /// the prizes they play for. Only possible before the competition starts.
+        ///
+        /// # Arguments
+        ///
+        /// * `lock_at_start` - Whether the sponsor pool locks at the start instead of the end.
+        pub fn set_sponsor_pool_lock(&mut self, lock_at_start: bool) {
+            self.assert_competition_not_started();
+            self.lock_sponsor_pool_at_start = lock_at_start;
+        }
+
+        /// Takes a sponsor's contributions out of the prize pools after the competition was
+        /// cancelled or settled without any players, so the admin can return them.
+        ///
+        /// # Arguments
+        ///
+        /// * `sponsor_id` - The ID of the sponsor.
+        ///
+        /// # Returns
+        ///
+        /// One bucket per contributed resource.
+        pub fn return_sponsor_contributions(&mut self, sponsor_id: u64) -> Vec<Bucket> {
+            let settled_without_players = self.get_status() == CompetitionStatus::Settled
+                && self.final_leaderboard.is_empty();
+            assert!(
+                self.get_status() == CompetitionStatus::Cancelled || settled_without_players,
+                "Competition has not been cancelled or settled without players."
+            );
+            let contributions = std::mem::take(
+                &mut self
+                    .sponsors
+                    .get_mut(&sponsor_id)
+                    .expect("Sponsor not found")
+                    .contributions,
+            );
+
+            contributions
+                .into_iter()
+                .map(|(resource_address, amount)| {
+                    self.prize_pools
+                        .get_mut(&resource_address)
+                        .expect("Prize pool not found")
+                        .take(amount)
+                })
+                .collect()
+        }
+
+        /// Sets how the prize pools are split between the best players.
+        ///
+        /// # Arguments
+        ///
+        /// * `payout_table` - The share of the prize pools for each rank, best first. The shares
+        ///   must be positive and add up to 1.
+        pub fn set_payout_table(&mut self, payout_table: Vec<Decimal>) {
+            self.assert_competition_not_started();
+            assert!(
+                payout_table.iter().all(|share| share.is_positive()),
+                "Every share of the payout table must be positive."
+            );
+            assert!(
+                payout_table
+                    .iter()
+                    .fold(Decimal::zero(), |sum, share| sum + *share)
+                    == Decimal::one(),
+                "The shares of the payout table must add up to 1."
+            );
+            self.payout_table = payout_table;
+        }
+
+        /// Makes the competition part of a season, which it reports its final ranking to when it
+        /// is settled. Only possible before the competition starts.
+        ///
+        /// Only the season itself can hand over its badge. The season is in the middle of
+        /// `Season::create_competition` when it calls this method, so the badge cannot be checked
+        /// against `Season::get_competition_badge_address` without re-entering the season.
+        /// Instead, the season named by the badge must be the caller and a `Season` of this
+        /// package, which only hands out badges of its own resource.
+        ///
+        /// # Arguments
+        ///
+        /// * `season_badge` - The badge minted for the competition by the season.
+        pub fn join_season(&mut self, season_badge: NonFungibleBucket) {
+            self.assert_competition_not_started();
+            assert!(
+                self.season.is_none(),
+                "The competition is already part of a season."
+            );
+
+            let season_address = season_badge
+                .non_fungible::<SeasonBadge>()
+                .data()
+                .season_address;
+            let season: Global<Season> = season_address.into();
+            assert!(
+                season.blueprint_id() == BlueprintId::new(&Runtime::package_address(), "Season"),
+                "The badge was not issued by a season."
+            );
+            Runtime::assert_access_rule(rule!(require(global_caller(season_address))));
+            self.season = Some((season, NonFungibleVault::with_bucket(season_badge)));
+        }
+
+        /// Calls off the competition. Users can then claim back their entry fees. Not possible
+        /// once a settlement has fixed the ranking.
+        pub fn cancel(&mut self) {
+            let ranking_fixed = self
+                .pending_settlement
+                .is_some_and(|(valued_count, _)| valued_count == self.participant_count);
+            assert!(!ranking_fixed, "The competition is being settled.");
+            self.transition_to(CompetitionStatus::Cancelled);
+
+            Runtime::emit_event(CompetitionCancelledEvent {
+                participant_count: self.participant_count,
+            });
+        }
+
+        /// Fixes the final ranking, awards the end-of-competition achievements and assigns the
+        /// prizes.
+        ///
+        /// The ranking is based on the current prices, so the portfolios can only be valued
+        /// within the settlement window after the competition's end. Once the window has passed
+        /// before every portfolio is valued, the competition can only be cancelled. Large
+        /// competitions are settled over several calls: the portfolios are valued first, then
+        /// the achievements are awarded, and the call completing both publishes the results.
+        ///
+        /// # Arguments
+        ///
+        /// * `batch_size` - The maximum number of portfolios to value or players to award in
+        ///   this call.
+        ///
+        /// # Returns
+        ///
+        /// The number of valuations and awards still to be made, zero once the competition is
+        /// settled.
+        pub fn settle(&mut self, batch_size: u64) -> u64 {
+            let (mut valued_count, mut awarded_count) = match self.pending_settlement {
+                Some(pending_settlement) => pending_settlement,
+                None => {
+                    assert!(
+                        self.get_status()
+                            .can_transition_to(CompetitionStatus::Settled),
+                        "The competition cannot be settled now."
+                    );
+                    self.final_leaderboard = Vec::new();
+                    (0, 0)
+                }
+            };
+            let mut budget = batch_size;
+
+            // Value the portfolios at the current prices
+            if valued_count < self.participant_count || self.pending_settlement.is_none() {
+                self.assert_settlement_window_open();
+
+                while valued_count < self.participant_count && budget > 0 {
+                    let user_id = self
+                        .participants
+                        .get(&valued_count)
+                        .expect("Participant not found")
+                        .clone();
+                    let entry = self.get_leaderboard_entry(&user_id);
+                    self.final_leaderboard.push(entry);
+                    valued_count += 1;
+                    budget -= 1;
+                }
+                if valued_count == self.participant_count {
+                    self.final_leaderboard
+                        .sort_by_key(|entry| Reverse(entry.score));
+                }
+            }
+
+            // Award the achievements of the fixed ranking
+            while valued_count == self.participant_count
+                && awarded_count < self.participant_count
+                && budget > 0
+            {
+                let rank = awarded_count as usize;
+                let user_id = self.final_leaderboard[rank].user_id.clone();
+                if rank < 3 {
+                    self.award_achievement(&user_id, Achievement::TopThreeFinish);
+                }
+                let liquidated = self
+                    .user_stats
+                    .get(&user_id)
+                    .is_some_and(|user_stats| user_stats.liquidated);
+                if !liquidated {
+                    self.award_achievement(&user_id, Achievement::SurvivedCompetition);
+                }
+                awarded_count += 1;
+                budget -= 1;
+            }
+
+            let remaining =
+                (self.participant_count - valued_count) + (self.participant_count - awarded_count);
+            if remaining > 0 {
+                self.pending_settlement = Some((valued_count, awarded_count));
+                return remaining;
+            }
+            self.pending_settlement = None;
+            self.publish_results();
+
+            0
+        }
+
+        /// Asserts that the settlement window after the competition's end has not passed.
+        fn assert_settlement_window_open(&self) {
+            let settlement_deadline = self
+                .competition_data
+                .competition_end
+                .add_seconds(SETTLEMENT_WINDOW)
+                .expect("Time overflow");
+            assert!(
+                Clock::current_time_is_strictly_before(
+                    settlement_deadline,
+                    TimePrecisionV2::Second
+                ),
+                "The settlement window has passed."
+            );
+        }
+
+        /// Publishes the fixed ranking of a settlement: settles the competition, assigns the
+        /// prizes, fixes the betting result and reports the ranking to the season.
+        fn publish_results(&mut self) {
+            self.transition_to(CompetitionStatus::Settled);
+            let leaderboard = self.final_leaderboard.clone();
+
+            let paid_ranks = self.payout_table.len().min(leaderboard.len());
+            let total_share = self.payout_table[..paid_ranks]
+                .iter()
+                .fold(Decimal::zero(), |sum, share| sum + *share);
+
+            for resource_address in self.prize_resource_addresses.clone() {
+                let mut pool_amount = self
+                    .prize_pools
+                    .get(&resource_address)
+                    .expect("Prize pool not found")
+                    .amount();
+                if resource_address == self.entry_fee_resource_address {
+                    pool_amount -= self.referral_fees_owed;
+                }
+                let divisibility = ResourceManager::from(resource_address)
+                    .resource_type()
+                    .divisibility()
+                    .expect("Prizes must be fungible");
+
+                for (rank, entry) in leaderboard.iter().take(paid_ranks).enumerate() {
+                    let amount = (pool_amount * self.payout_table[rank])
+                        .checked_div(total_share)
+                        .expect("Division by zero error")
+                        .checked_round(divisibility, RoundingMode::ToZero)
+                        .expect("Rounding error");
+
+                    // Leaders receive their share of the prizes of followers who made a profit
+                    let mut fees = Decimal::zero();
+                    for (leader_id, profit_share) in self.get_profit_shares_owed(entry) {
+                        let fee = (amount * profit_share)
+                            .checked_round(divisibility, RoundingMode::ToZero)
+                            .expect("Rounding error");
+                        self.add_prize(&leader_id, resource_address, fee);
+                        Runtime::emit_event(ProfitSharePaidEvent {
+                            user_id: entry.user_id.clone(),
+                            leader_id,
+                            resource_address,
+                            amount: fee,
+                        });
+                        fees += fee;
+                    }
+                    self.add_prize(&entry.user_id, resource_address, amount - fees);
+                }
+            }
+
+            if let Some(winner) = leaderboard.first() {
+                self.betting_result = Some(self.calculate_betting_result(&winner.user_id));
+            }
+
+            if let Some((season, season_badge)) = &self.season {
+                let season_badge_proof = season_badge
+                    .create_proof_of_non_fungibles(&season_badge.non_fungible_local_ids(1));
+                // Only the players earning season points are reported
+                let ranked_count = season.get_points_table().len();
+                season.report_results(
+                    season_badge_proof.into(),
+                    leaderboard
+                        .iter()
+                        .take(ranked_count)
+                        .map(|entry| entry.user_id.clone())
+                        .collect(),
+                );
+            }
+
+            Runtime::emit_event(CompetitionSettledEvent {
+                winners: leaderboard
+                    .iter()
+                    .take(paid_ranks)
+                    .map(|entry| entry.user_id.clone())
+                    .collect(),
+            });
+        }
+
+        /// Gets the leaders a user owes a share of their prize to, if the user ends the
+        /// competition above their starting balance. Every leader whose trades were mirrored
+        /// receives their agreed profit share, weighted by the FUSD value mirrored from their
+        /// trades.
+        ///
+        /// # Arguments
+        ///
+        /// * `entry` - The user's final position on the leaderboard.
+        ///
+        /// # Returns
+        ///
+        /// The ID of each leader and the share of the prize they receive.
+        fn get_profit_shares_owed(&self, entry: &LeaderboardEntry) -> Vec<(String, Decimal)> {
+            let Some(user_stats) = self.user_stats.get(&entry.user_id) else {
+                return Vec::new();
+            };
+            if entry.portfolio_value <= user_stats.starting_balance {
+                return Vec::new();
+            }
+
+            let total_mirrored = user_stats
+                .copied_leaders
+                .iter()
+                .fold(Decimal::zero(), |total, (_, _, value)| total + *value);
+            user_stats
+                .copied_leaders
+                .iter()
+                .filter(|(_, profit_share, _)| profit_share.is_positive())
+                .map(|(leader_id, profit_share, value)| {
+                    let share = (*profit_share * *value)
+                        .checked_div(total_mirrored)
+                        .expect("Division by zero error");
+                    (leader_id.clone(), share)
+                })
+                .collect()
+        }
+
+        /// Adds an amount to the prize a user can claim.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        /// * `resource_address` - The resource of the prize.
+        /// * `amount` - The amount to add.
+        fn add_prize(&mut self, user_id: &str, resource_address: ResourceAddress, amount: Decimal) {
+            let user_id = user_id.to_string();
+            let prize = self.prizes.get_mut(&user_id);
+            if let Some(mut prize) = prize {
+                prize.push((resource_address, amount));
+            } else {
+                drop(prize);
+                self.prizes
+                    .insert(user_id, vec![(resource_address, amount)]);
+            }
+        }
+
+        /// Withdraws the prizes a user has won. If the prizes vest, the user receives a prize
+        /// claim instead, which is used to withdraw the prize as it unlocks.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        ///
+        /// # Returns
+        ///
+        /// One bucket of tokens or one prize claim per prize resource.
+        pub fn claim_prize(&mut self, user_token_proof: Proof) -> Vec<Bucket> {
+            assert!(
+                self.get_status() == CompetitionStatus::Settled,
+                "Competition has not been settled yet."
+            );
+            let user_id = self.extract_user_id(user_token_proof);
+            let prize = self.prizes.remove(&user_id).expect("No prize to claim.");
+
+            prize
+                .into_iter()
+                .map(|(resource_address, amount)| {
+                    Runtime::emit_event(PrizeClaimedEvent {
+                        user_id: user_id.clone(),
+                        resource_address,
+                        amount,
+                    });
+                    match self.prize_vesting {
+                        Some(schedule) => self
+                            .prize_claim_manager
+                            .mint_ruid_non_fungible(PrizeClaim {
+                                resource_address,
+                                total_amount: amount,
+                                claimed_amount: Decimal::zero(),
+                                vesting_start: self.competition_data.competition_end,
+                                schedule,
+                            })
+                            .into(),
+                        None => self
+                            .prize_pools
+                            .get_mut(&resource_address)
+                            .expect("Prize pool not found")
+                            .take(amount),
+                    }
+                })
+                .collect()
+        }
+
+        /// Withdraws the unlocked part of a vesting prize.
+        ///
+        /// # Arguments
+        ///
+        /// * `prize_claim_proof` - A proof of the prize claim.
+        ///
+        /// # Returns
+        ///
+        /// A bucket containing the prize tokens unlocked since the last withdrawal.
+        pub fn withdraw_vested_prize(&mut self, prize_claim_proof: Proof) -> Bucket {
+            let claim_id = prize_claim_proof
+                .check(self.prize_claim_manager.address())
+                .as_non_fungible()
+                .non_fungible_local_id();
+            let amount = self.get_claimable_prize(claim_id.clone());
+            assert!(amount.is_positive(), "No unlocked prize to withdraw.");
+
+            let prize_claim: PrizeClaim = self.prize_claim_manager.get_non_fungible_data(&claim_id);
+            self.prize_claim_manager.update_non_fungible_data(
+                &claim_id,
+                "claimed_amount",
+                prize_claim.claimed_amount + amount,
+            );
+
+            Runtime::emit_event(VestedPrizeWithdrawnEvent {
+                claim_id,
+                resource_address: prize_claim.resource_address,
+                amount,
+            });
+
+            self.prize_pools
+                .get_mut(&prize_claim.resource_address)
+                .expect("Prize pool not found")
+                .take(amount)
+        }
+
+        /// Withdraws the entry fees a referrer has earned from the players they referred. Only
+        /// referrers who are registered can claim.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the referrer's user token to verify identity.
+        ///
+        /// # Returns
+        ///
+        /// A bucket containing the referral fees.
+        pub fn claim_referral_fees(&mut self, user_token_proof: Proof) -> Bucket {
+            assert!(
+                self.get_status() == CompetitionStatus::Settled,
+                "Competition has not been settled yet."
+            );
+            let referrer_id = self.extract_user_id(user_token_proof);
+            assert!(
+                self.is_participant(&referrer_id),
+                "The referrer is not registered."
+            );
+            let amount = {
+                let mut referrer_stats = self
+                    .referrer_stats
+                    .get_mut(&referrer_id)
+                    .expect("No referral fees to claim.");
+                let amount = referrer_stats.fees_earned - referrer_stats.fees_claimed;
+                assert!(amount.is_positive(), "No referral fees to claim.");
+                referrer_stats.fees_claimed = referrer_stats.fees_earned;
+                amount
+            };
+            self.referral_fees_owed -= amount;
+
+            Runtime::emit_event(ReferralFeesClaimedEvent {
+                referrer_id,
+                amount,
+            });
+
+            self.prize_pools
+                .get_mut(&self.entry_fee_resource_address)
+                .expect("Prize pool not found")
+                .take(amount)
+        }
+
+        /// Pays back the entry fee of a user after the competition was cancelled.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        ///
+        /// # Returns
+        ///
+        /// A bucket containing the entry fee.
+        pub fn claim_refund(&mut self, user_token_proof: Proof) -> Bucket {
+            assert!(
+                self.get_status() == CompetitionStatus::Cancelled,
+                "Competition has not been cancelled."
+            );
+            let user_id = self.extract_user_id(user_token_proof);
+            self.refund_entry_fee(&user_id)
+        }
+
+        /// Takes the entry fee a user paid out of the prize pool.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// A bucket containing the entry fee, empty if it was already refunded.
+        fn refund_entry_fee(&mut self, user_id: &str) -> Bucket {
+            let user_id = user_id.to_string();
+            let (amount, referral_fee) = {
+                let mut user_stats = self
+                    .user_stats
+                    .get_mut(&user_id)
+                    .expect("User is not registered.");
+                (
+                    std::mem::take(&mut user_stats.entry_fee_paid),
+                    std::mem::take(&mut user_stats.referral_fee),
+                )
+            };
+
+            // The referrer loses the share of a refunded entry fee
+            if referral_fee.is_positive() {
+                let referrer_id = self
+                    .referrers
+                    .get(&user_id)
+                    .expect("Referrer not found")
+                    .clone();
+                self.referrer_stats
+                    .get_mut(&referrer_id)
+                    .expect("Referrer stats not found")
+                    .fees_earned -= referral_fee;
+                self.referral_fees_owed -= referral_fee;
+            }
+
+            Runtime::emit_event(EntryFeeRefundedEvent { user_id, amount });
+
+            match self.prize_pools.get_mut(&self.entry_fee_resource_address) {
+                Some(mut prize_pool) => prize_pool.take(amount),
+                None => Bucket::new(self.entry_fee_resource_address),
+            }
+        }
+
+        /// Burns the virtual assets of the next participants once the competition is over, so
+        /// the supply of the competition's own virtual resources drops back to zero.
+        ///
+        /// Anyone can call this method after the competition was settled or cancelled. Large
+        /// competitions are processed over several calls.
+        ///
+        /// # Arguments
+        ///
+        /// * `batch_size` - The maximum number of user asset vaults to empty in this call.
+        ///
+        /// # Returns
+        ///
+        /// The number of user asset vaults that still hold virtual assets.
+        pub fn burn_virtual_assets(&mut self, batch_size: u64) -> u64 {
+            assert!(
+                self.get_status().is_final(),
+                "Competition has not been settled or cancelled yet."
+            );
+
+            let batch_end = self
+                .burned_vault_count
+                .saturating_add(batch_size)
+                .min(self.participant_count);
+            for index in self.burned_vault_count..batch_end {
+                let user_id = self
+                    .participants
+                    .get(&index)
+                    .expect("Participant not found")
+                    .clone();
+                self.user_asset_vaults
+                    .get(&user_id)
+                    .expect("User vault not found")
+                    .burn_all_assets();
+            }
+            self.burned_vault_count = batch_end;
+
+            self.participant_count - batch_end
+        }
+
+        /// Gets the current status of the competition.
+        ///
+        /// # Returns
+        ///
+        /// The final status if the competition was settled or cancelled, otherwise the status
+        /// following from the competition times.
+        pub fn get_status(&self) -> CompetitionStatus {
+            if self.status.is_final() {
+                return self.status;
+            }
+
+            let is_before = |time: Instant| {
+                Clock::current_time_is_strictly_before(time, TimePrecisionV2::Second)
+            };
+            if is_before(self.competition_data.registration_start) {
+                CompetitionStatus::Created
+            } else if is_before(self.competition_data.competition_start) {
+                CompetitionStatus::RegistrationOpen
+            } else if is_before(self.competition_data.competition_end) {
+                CompetitionStatus::Running
+            } else {
+                CompetitionStatus::Ended
+            }
+        }
+
+        /// Gets the entry fee users pay at registration.
+        ///
+        /// # Returns
+        ///
+        /// The resource and amount of the entry fee.
+        pub fn get_entry_fee(&self) -> (ResourceAddress, Decimal) {
+            (self.entry_fee_resource_address, self.entry_fee)
+        }
+
+        /// Gets the amounts in the prize pools.
+        ///
+        /// # Returns
+        ///
+        /// The resource and amount of every prize pool.
+        pub fn get_prize_pools(&self) -> Vec<(ResourceAddress, Decimal)> {
+            self.prize_resource_addresses
+                .iter()
+                .map(|resource_address| {
+                    let amount = self
+                        .prize_pools
+                        .get(resource_address)
+                        .expect("Prize pool not found")
+                        .amount();
+                    (*resource_address, amount)
+                })
+                .collect()
+        }
+
+        /// Gets the sponsors of the competition and what they contributed.
+        ///
+        /// # Returns
+        ///
+        /// The sponsor IDs and sponsors, in the order they were added.
+        pub fn get_sponsors(&self) -> Vec<(u64, Sponsor)> {
+            (0..self.sponsor_count)
+                .map(|sponsor_id| {
+                    (
+                        sponsor_id,
+                        self.sponsors
+                            .get(&sponsor_id)
+                            .expect("Sponsor not found")
+                            .clone(),
+                    )
+                })
+                .collect()
+        }
+
+        /// Checks whether sponsors can no longer deposit. Deposits close at the competition start
+        /// if the sponsor pool locks then, otherwise at the competition end.
+        pub fn is_sponsor_pool_locked(&self) -> bool {
+            let lock_status = if self.lock_sponsor_pool_at_start {
+                CompetitionStatus::Running
+            } else {
+                CompetitionStatus::Ended
+            };
+            self.get_status() >= lock_status
+        }
+
+        /// Gets the vesting schedule of prizes, `None` if prizes are paid at once.
+        pub fn get_prize_vesting(&self) -> Option<VestingSchedule> {
+            self.prize_vesting
+        }
+
+        /// Gets the resource address of prize claims.
+        pub fn get_prize_claim_address(&self) -> ResourceAddress {
+            self.prize_claim_manager.address()
+        }
+
+        /// Gets the amount of a vesting prize that can be withdrawn now.
+        ///
+        /// # Arguments
+        ///
+        /// * `claim_id` - The ID of the prize claim.
+        pub fn get_claimable_prize(&self, claim_id: NonFungibleLocalId) -> Decimal {
+            let prize_claim: PrizeClaim = self.prize_claim_manager.get_non_fungible_data(&claim_id);
+            let divisibility = ResourceManager::from(prize_claim.resource_address)
+                .resource_type()
+                .divisibility()
+                .expect("Prizes must be fungible");
+            prize_claim.claimable_amount(Clock::current_time(TimePrecisionV2::Second), divisibility)
+        }
+
+        /// Gets how the prize pools are split between the best players.
+        ///
+        /// # Returns
+        ///
+        /// The share of the prize pools for each rank, best first.
+        pub fn get_payout_table(&self) -> Vec<Decimal> {
+            self.payout_table.clone()
+        }
+
+        /// Gets the unclaimed prize of a user.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// The resource and amount of every unclaimed prize.
+        pub fn get_prize(&self, user_id: String) -> Vec<(ResourceAddress, Decimal)> {
+            self.prizes
+                .get(&user_id)
+                .map(|prize| prize.clone())
+                .unwrap_or_default()
+        }
+
+        /// Emits an event containing the current competition times.
+        fn emit_competition_time_changed(&self) {
+            Runtime::emit_event(CompetitionTimeChangedEvent {
+                registration_start: self.competition_data.registration_start,
+                registration_end: self.competition_data.registration_end,
+                competition_start: self.competition_data.competition_start,
+                competition_end: self.competition_data.competition_end,
+            });
+        }
+
+        /// Gets the time from which users can register.
+        ///
+        /// # Returns
+        ///
+        /// The registration start time.
+        pub fn get_registration_start_time(&self) -> Instant {
+            self.competition_data.registration_start
+        }
+
+        /// Gets the time until which users can register.
+        ///
+        /// # Returns
+        ///
+        /// The registration end time.
+        pub fn get_registration_end_time(&self) -> Instant {
+            self.competition_data.registration_end
+        }
+
+        /// Gets the starting balance policy for users who register after the competition started.
+        ///
+        /// # Returns
+        ///
+        /// The late registration policy.
+        pub fn get_late_registration_policy(&self) -> LateRegistrationPolicy {
+            self.late_registration_policy
+        }
+
+        /// Gets the maximum number of participants.
+        ///
+        /// # Returns
+        ///
+        /// The participant limit, or `None` if there is no limit.
+        pub fn get_max_participants(&self) -> Option<u64> {
+            self.max_participants
+        }
+
+        /// Gets who is allowed to register for the competition.
+        ///
+        /// # Returns
+        ///
+        /// The registration access of the competition.
+        pub fn get_registration_access(&self) -> RegistrationAccess {
+            self.registration_access
+        }
+
+        /// Checks whether a user is on the allowlist.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// `true` if the user is on the allowlist.
+        pub fn is_allowlisted(&self, user_id: String) -> bool {
+            self.allowlist.get(&user_id).is_some()
+        }
+
+        /// Gets the competition start time.
+        ///
+        /// # Returns
+        ///
+        /// The start time of the competition.
+        pub fn get_competition_start_time(&self) -> Instant {
+            self.competition_data.competition_start
+        }
+
+        /// Gets the competition end time.
+        ///
+        /// # Returns
+        ///
+        /// The end time of the competition.
+        pub fn get_competition_end_time(&self) -> Instant {
+            self.competition_data.competition_end
+        }
+
+        /// Sets the name, description and image of the badge minted for an achievement.
+        ///
+        /// Badges that have already been minted keep their data.
+        ///
+        /// # Arguments
+        ///
+        /// * `achievement` - The achievement to configure.
+        /// * `config` - The badge data to use for the achievement.
+        pub fn set_achievement_config(
+            &mut self,
+            achievement: Achievement,
+            config: AchievementConfig,
+        ) {
+            self.achievement_configs.insert(achievement, config);
+        }
+
+        /// Gets the resource address of the achievement badges.
+        ///
+        /// # Returns
+        ///
+        /// The resource address of the achievement badges.
+        pub fn get_achievement_badge_address(&self) -> ResourceAddress {
+            self.achievement_badge_manager.address()
+        }
+
+        /// Gets the number of trades a user has executed in this competition.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// The number of executed trades.
+        pub fn get_trade_count(&self, user_id: String) -> u64 {
+            self.user_stats
+                .get(&user_id)
+                .map(|user_stats| user_stats.trade_count)
+                .unwrap_or_default()
+        }
+
+        /// Gets a page of a user's trade history, oldest trade first.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        /// * `offset` - The index of the first trade to return.
+        /// * `limit` - The maximum number of trades to return.
+        ///
+        /// # Returns
+        ///
+        /// The trades in the requested range.
+        pub fn get_trade_history(
+            &self,
+            user_id: String,
+            offset: u64,
+            limit: u64,
+        ) -> Vec<TradeRecord> {
+            let end = offset
+                .saturating_add(limit)
+                .min(self.get_trade_count(user_id.clone()));
+
+            (offset..end)
+                .map(|trade_index| {
+                    self.trade_history
+                        .get(&(user_id.clone(), trade_index))
+                        .expect("Trade not found")
+                        .clone()
+                })
+                .collect()
+        }
+
+        /// Gets the holdings of a user, valued in FUSD at the current oracle prices.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// The user's positions and their total FUSD value.
+        pub fn get_user_portfolio(&self, user_id: String) -> Portfolio {
+            self.value_portfolio(&user_id, self.get_prices())
+        }
+
+        /// Values the holdings, the open perpetual positions and the options of a user.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        /// * `prices` - The FUSD price of every virtual asset.
+        ///
+        /// # Returns
+        ///
+        /// The user's positions and their total FUSD value.
+        fn value_portfolio(
+            &self,
+            user_id: &str,
+            prices: IndexMap<ResourceAddress, Decimal>,
+        ) -> Portfolio {
+            let mut portfolio = self
+                .user_asset_vaults
+                .get(&user_id.to_string())
+                .expect("User vault not found")
+                .get_portfolio(prices.clone());
+
+            // Losses beyond the margin are not charged, so positions count at most down to zero
+            portfolio.perpetual_value = self
+                .value_perpetual_positions(user_id, &prices)
+                .iter()
+                .fold(Decimal::zero(), |total, position| {
+                    total + position.equity.max(Decimal::zero())
+                });
+            portfolio.option_value = self
+                .value_option_positions(user_id, &prices)
+                .iter()
+                .fold(Decimal::zero(), |total, option| total + option.value);
+            portfolio.total_value += portfolio.perpetual_value + portfolio.option_value;
+            portfolio
+        }
+
+        /// Gets the FUSD price of every virtual asset from the prices of its reference asset.
+        ///
+        /// # Returns
+        ///
+        /// The price of every virtual asset in FUSD.
+        fn get_prices(&self) -> IndexMap<ResourceAddress, Decimal> {
+            let oracle: Global<SimpleOracle> = self.trade_simulator.get_oracle_address().into();
+            let fusd_price =
+                oracle.get_price(self.get_reference_address(self.fusd_resource_address));
+
+            self.virtual_asset_addresses
+                .iter()
+                .map(|resource_address| {
+                    let price = if *resource_address == self.fusd_resource_address {
+                        Decimal::one()
+                    } else {
+                        oracle
+                            .get_price(self.get_reference_address(*resource_address))
+                            .checked_div(fusd_price)
+                            .expect("Division by zero error")
+                    };
+                    (*resource_address, price)
+                })
+                .collect()
+        }
+
+        /// Gets the FUSD price of a single virtual asset, which is the mark price of its
+        /// perpetual market.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The resource address of the virtual asset.
+        ///
+        /// # Returns
+        ///
+        /// The price of the asset in FUSD.
+        fn get_mark_price(&self, resource_address: ResourceAddress) -> Decimal {
+            let oracle: Global<SimpleOracle> = self.trade_simulator.get_oracle_address().into();
+            oracle
+                .get_price(self.get_reference_address(resource_address))
+                .checked_div(
+                    oracle.get_price(self.get_reference_address(self.fusd_resource_address)),
+                )
+                .expect("Division by zero error")
+        }
+
+        /// Gets the FUSD price a virtual asset had at a point in time, e.g. the expiry of an
+        /// option.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The resource address of the virtual asset.
+        /// * `time` - The point in time.
+        ///
+        /// # Returns
+        ///
+        /// The price of the asset in FUSD at that time.
+        fn get_mark_price_at(&self, resource_address: ResourceAddress, time: Instant) -> Decimal {
+            let oracle: Global<SimpleOracle> = self.trade_simulator.get_oracle_address().into();
+            oracle
+                .get_price_at(self.get_reference_address(resource_address), time)
+                .checked_div(
+                    oracle
+                        .get_price_at(self.get_reference_address(self.fusd_resource_address), time),
+                )
+                .expect("Division by zero error")
+        }
+
+        /// Gets the resource a virtual asset is priced by.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The address of the virtual asset.
+        ///
+        /// # Returns
+        ///
+        /// The address of the reference asset.
+        fn get_reference_address(&self, resource_address: ResourceAddress) -> ResourceAddress {
+            *self
+                .reference_addresses
+                .get(&resource_address)
+                .expect("The asset is not traded in this competition.")
+        }
+
+        /// Creates a virtual asset that only the competition can mint and burn.
+        ///
+        /// Deposits, withdrawals and burns are restricted to the competition and the blueprints
+        /// of this package, which the user asset vaults belong to. Players cannot move the asset
+        /// to an account or sell it elsewhere.
+        ///
+        /// # Arguments
+        ///
+        /// * `component_address` - The address of the competition.
+        /// * `symbol` - The symbol of the reference asset, e.g. "BTC".
+        /// * `competition_name` - The name of the competition.
+        ///
+        /// # Returns
+        ///
+        /// The address of the new resource.
+        fn create_virtual_asset(
+            component_address: ComponentAddress,
+            symbol: &str,
+            competition_name: &str,
+        ) -> ResourceAddress {
+            let game_component_rule = rule!(
+                require(global_caller(component_address))
+                    || require(package_of_direct_caller(Runtime::package_address()))
+            );
+
+            ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(DIVISIBILITY_MAXIMUM)
+                .metadata(metadata! {
+                    init {
+                        "name" => format!("c{} – {}", symbol, competition_name), locked;
+                        "symbol" => format!("c{}", symbol), locked;
+                        "description" => format!("Virtual {} of the {} competition.", symbol, competition_name), locked;
+                        "tags" => ["virtual"], locked;
+                    }
+                })
+                .mint_roles(mint_roles! {
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles! {
+                    burner => game_component_rule.clone();
+                    burner_updater => rule!(deny_all);
+                })
+                .withdraw_roles(withdraw_roles! {
+                    withdrawer => game_component_rule.clone();
+                    withdrawer_updater => rule!(deny_all);
+                })
+                .deposit_roles(deposit_roles! {
+                    depositor => game_component_rule;
+                    depositor_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply()
+                .address()
+        }
+
+        /// Creates the resource of the competition's options. Like the virtual assets, options
+        /// can only be held in the user asset vaults.
+        ///
+        /// # Arguments
+        ///
+        /// * `component_address` - The address of the competition.
+        /// * `competition_name` - The name of the competition.
+        ///
+        /// # Returns
+        ///
+        /// The resource manager of the options.
+        fn create_option_resource(
+            component_address: ComponentAddress,
+            competition_name: &str,
+        ) -> NonFungibleResourceManager {
+            let game_component_rule = rule!(
+                require(global_caller(component_address))
+                    || require(package_of_direct_caller(Runtime::package_address()))
+            );
+
+            ResourceBuilder::new_ruid_non_fungible::<OptionContract>(OwnerRole::None)
+                .metadata(metadata! {
+                    init {
+                        "name" => format!("Option – {}", competition_name), locked;
+                        "symbol" => "cOPT", locked;
+                        "description" => format!("A simulated option of the {} competition.", competition_name), locked;
+                        "tags" => ["virtual", "option"], locked;
+                    }
+                })
+                .mint_roles(mint_roles! {
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles! {
+                    burner => game_component_rule.clone();
+                    burner_updater => rule!(deny_all);
+                })
+                .withdraw_roles(withdraw_roles! {
+                    withdrawer => game_component_rule.clone();
+                    withdrawer_updater => rule!(deny_all);
+                })
+                .deposit_roles(deposit_roles! {
+                    depositor => game_component_rule;
+                    depositor_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply()
+        }
+
+        /// Creates the resource of the prize claims handed out when prizes vest.
+        ///
+        /// # Arguments
+        ///
+        /// * `component_address` - The address of the competition.
+        /// * `competition_name` - The name of the competition.
+        ///
+        /// # Returns
+        ///
+        /// The resource manager of the prize claims.
+        fn create_prize_claim_resource(
+            component_address: ComponentAddress,
+            competition_name: &str,
+        ) -> NonFungibleResourceManager {
+            ResourceBuilder::new_ruid_non_fungible::<PrizeClaim>(OwnerRole::None)
+                .metadata(metadata! {
+                    init {
+                        "name" => format!("Prize Claim – {}", competition_name), locked;
+                        "symbol" => "CLAIM", locked;
+                        "description" => format!("A claim on a vesting prize of the {} competition.", competition_name), locked;
+                        "tags" => ["badge", "prize"], locked;
+                    }
+                })
+                .mint_roles(mint_roles! {
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .non_fungible_data_update_roles(non_fungible_data_update_roles! {
+                    non_fungible_data_updater => rule!(require(global_caller(component_address)));
+                    non_fungible_data_updater_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply()
+        }
+
+        /// Creates the competition's own version of a whitelisted asset. Only possible before the
+        /// competition starts.
+        ///
+        /// # Arguments
+        ///
+        /// * `reference_address` - The resource the asset is priced by in the oracle and the
+        ///   trade simulator.
+        /// * `symbol` - The symbol of the reference asset, e.g. "BTC".
+        ///
+        /// # Returns
+        ///
+        /// The address of the new virtual asset.
+        pub fn add_virtual_asset(
+            &mut self,
+            reference_address: ResourceAddress,
+            symbol: String,
+        ) -> ResourceAddress {
+            self.assert_competition_not_started();
+            assert!(
+                self.get_virtual_assets()
+                    .iter()
+                    .all(|(_, existing_reference)| *existing_reference != reference_address),
+                "The asset is already traded in this competition."
+            );
+
+            let resource_address =
+                Self::create_virtual_asset(Runtime::global_address(), &symbol, &self.name);
+            self.reference_addresses
+                .insert(resource_address, reference_address);
+            self.virtual_asset_addresses.push(resource_address);
+
+            Runtime::emit_event(VirtualAssetCreatedEvent {
+                resource_address,
+                reference_address,
+            });
+
+            resource_address
+        }
+
+        /// Gets the name of the competition.
+        ///
+        /// # Returns
+        ///
+        /// The name of the competition.
+        pub fn get_name(&self) -> String {
+            self.name.clone()
+        }
+
+        /// Gets the address of the competition's own FUSD.
+        ///
+        /// # Returns
+        ///
+        /// The resource address of the virtual FUSD.
+        pub fn get_fusd_address(&self) -> ResourceAddress {
+            self.fusd_resource_address
+        }
+
+        /// Gets the virtual assets of the competition.
+        ///
+        /// # Returns
+        ///
+        /// Every virtual asset together with the resource it is priced by, FUSD first.
+        pub fn get_virtual_assets(&self) -> Vec<(ResourceAddress, ResourceAddress)> {
+            self.virtual_asset_addresses
+                .iter()
+                .map(|resource_address| {
+                    (
+                        *resource_address,
+                        self.get_reference_address(*resource_address),
+                    )
+                })
+                .collect()
+        }
+
+        /// Gets the IDs of all registered users, in order of registration. A user who
+        /// unregisters is replaced by the latest registered user.
+        ///
+        /// # Returns
+        ///
+        /// The list of user IDs.
+        pub fn get_participants(&self) -> Vec<String> {
+            (0..self.participant_count)
+                .map(|index| {
+                    self.participants
+                        .get(&index)
+                        .expect("Participant not found")
+                        .clone()
+                })
+                .collect()
+        }
+
+        /// Sets the minimum time between two portfolio snapshots.
+        ///
+        /// # Arguments
+        ///
+        /// * `seconds` - The snapshot interval in seconds.
+        pub fn set_snapshot_interval(&mut self, seconds: i64) {
+            assert!(seconds > 0, "The snapshot interval must be positive.");
+            self.snapshot_interval = seconds;
+        }
+
+        /// Gets the minimum time between two portfolio snapshots.
+        ///
+        /// # Returns
+        ///
+        /// The snapshot interval in seconds.
+        pub fn get_snapshot_interval(&self) -> i64 {
+            self.snapshot_interval
+        }
+
+        /// Gets the time of the latest portfolio snapshot.
+        ///
+        /// # Returns
+        ///
+        /// The time of the latest snapshot, if any snapshot has been taken.
+        pub fn get_last_snapshot_time(&self) -> Option<Instant> {
+            self.last_snapshot
+        }
+
+        /// Gets the portfolio values recorded for a user by the completed snapshots.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// The user's equity curve, oldest snapshot first.
+        pub fn get_equity_curve(&self, user_id: String) -> Vec<EquityPoint> {
+            let Some(last_snapshot) = self.last_snapshot else {
+                return Vec::new();
+            };
+            self.equity_curves
+                .get(&user_id)
+                .map(|equity_curve| {
+                    equity_curve
+                        .iter()
+                        .filter(|point| point.timestamp <= last_snapshot)
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        /// Sets the rule used to rank the players. Only possible before the competition starts.
+        ///
+        /// # Arguments
+        ///
+        /// * `scoring_rule` - The new scoring rule.
+        pub fn set_scoring_rule(&mut self, scoring_rule: ScoringRule) {
+            self.assert_competition_not_started();
+            self.scoring_rule = scoring_rule;
+        }
+
+        /// Gets the rule used to rank the players.
+        ///
+        /// # Returns
+        ///
+        /// The scoring rule of the competition.
+        pub fn get_scoring_rule(&self) -> ScoringRule {
+            self.scoring_rule
+        }
+
+        /// Sets the limits on the players' trades. Only possible before the competition starts.
+        ///
+        /// # Arguments
+        ///
+        /// * `risk_rules` - The new risk rules.
+        pub fn set_risk_rules(&mut self, risk_rules: RiskRules) {
+            self.assert_competition_not_started();
+            risk_rules.assert_valid();
+            self.risk_rules = risk_rules;
+        }
+
+        /// Gets the limits on the players' trades.
+        ///
+        /// # Returns
+        ///
+        /// The risk rules of the competition.
+        pub fn get_risk_rules(&self) -> RiskRules {
+            self.risk_rules.clone()
+        }
+
+        /// Sets the protections against trading on stale prices. Only possible before the
+        /// competition starts.
+        ///
+        /// # Arguments
+        ///
+        /// * `trade_protection` - The new trade protections.
+        pub fn set_trade_protection(&mut self, trade_protection: TradeProtection) {
+            self.assert_competition_not_started();
+            trade_protection.assert_valid();
+            self.trade_protection = trade_protection;
+        }
+
+        /// Gets the protections against trading on stale prices.
+        ///
+        /// # Returns
+        ///
+        /// The trade protections of the competition.
+        pub fn get_trade_protection(&self) -> TradeProtection {
+            self.trade_protection.clone()
+        }
+
+        /// Opens a perpetual market on a virtual asset. Only possible before the competition
+        /// starts.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The virtual asset to trade perpetuals on.
+        /// * `config` - The leverage, funding and margin parameters of the market.
+        pub fn add_perpetual_market(
+            &mut self,
+            resource_address: ResourceAddress,
+            config: PerpetualMarketConfig,
+        ) {
+            self.assert_competition_not_started();
+            assert!(
+                resource_address != self.fusd_resource_address,
+                "FUSD cannot have a perpetual market."
+            );
+            self.get_reference_address(resource_address);
+            assert!(
+                self.perpetual_markets.get(&resource_address).is_none(),
+                "The perpetual market already exists."
+            );
+            config.assert_valid();
+
+            self.perpetual_markets.insert(
+                resource_address,
+                PerpetualMarket::new(config, Clock::current_time(TimePrecisionV2::Second)),
+            );
+        }
+
+        /// Sets the implied volatility options on a virtual asset are priced with. Setting it
+        /// for the first time opens the asset for options trading.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The virtual asset the options are written on.
+        /// * `volatility` - The annualized implied volatility, e.g. 0.8 for 80%.
+        pub fn set_option_volatility(
+            &mut self,
+            resource_address: ResourceAddress,
+            volatility: Decimal,
+        ) {
+            assert!(
+                resource_address != self.fusd_resource_address,
+                "FUSD cannot have options."
+            );
+            self.get_reference_address(resource_address);
+            assert!(volatility.is_positive(), "The volatility must be positive.");
+
+            self.option_volatilities
+                .insert(resource_address, volatility);
+        }
+
+        /// Sets the keeper reward and the rules against farming it.
+        ///
+        /// # Arguments
+        ///
+        /// * `config` - The reward per executed action and its limits.
+        pub fn set_keeper_reward_config(&mut self, config: KeeperRewardConfig) {
+            config.assert_valid();
+            self.keeper_reward_config = config;
+        }
+
+        /// Gets the keeper reward and the rules against farming it.
+        ///
+        /// # Returns
+        ///
+        /// The keeper reward configuration of the competition.
+        pub fn get_keeper_reward_config(&self) -> KeeperRewardConfig {
+            self.keeper_reward_config.clone()
+        }
+
+        /// Gets the XRD left for keeper rewards.
+        ///
+        /// # Returns
+        ///
+        /// The amount of XRD in the keeper reward vault.
+        pub fn get_keeper_reward_balance(&self) -> Decimal {
+            self.keeper_rewards.amount()
+        }
+
+        /// Sets the time until which spectators can bet. It can be after the competition start
+        /// to keep betting open while players trade. Not possible once betting has closed.
+        ///
+        /// # Arguments
+        ///
+        /// * `time` - The betting close time, at the latest the competition end.
+        pub fn set_betting_close_time(&mut self, time: Instant) {
+            assert!(
+                !self.get_status().is_final(),
+                "Competition has already been settled or cancelled."
+            );
+            assert!(
+                Clock::current_time_is_strictly_before(
+                    self.get_betting_close_time(),
+                    TimePrecisionV2::Second
+                ),
+                "Betting has closed."
+            );
+            assert!(
+                time <= self.competition_data.competition_end,
+                "Betting must close before the competition ends."
+            );
+            self.betting_close = Some(time);
+        }
+
+        /// Sets the rewards referrers earn for each referred player. Players who registered
+        /// earlier keep the fee share of their registration.
+        ///
+        /// # Arguments
+        ///
+        /// * `referral_config` - The referral rewards.
+        pub fn set_referral_config(&mut self, referral_config: ReferralConfig) {
+            referral_config.assert_valid();
+            self.referral_config = referral_config;
+        }
+
+        /// Ranks all participants by the scoring rule of the competition. Every player is scored
+        /// against the FUSD they received when registering, so late registrants are not ranked
+        /// by their reduced balance. Referral bonuses are not part of that baseline.
+        ///
+        /// # Returns
+        ///
+        /// The leaderboard, best player first. Ties keep the order of the participants. Once the
+        /// competition is settled, the final leaderboard.
+        pub fn get_leaderboard(&self) -> Vec<LeaderboardEntry> {
+            if self.get_status() == CompetitionStatus::Settled {
+                return self.final_leaderboard.clone();
+            }
+
+            let mut leaderboard: Vec<LeaderboardEntry> = self
+                .get_participants()
+                .iter()
+                .map(|user_id| self.get_leaderboard_entry(user_id))
+                .collect();
+
+            leaderboard.sort_by_key(|entry| Reverse(entry.score));
+            leaderboard
+        }
+
+        /// Values and scores a participant at the current prices.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the participant.
+        ///
+        /// # Returns
+        ///
+        /// The participant's leaderboard entry.
+        fn get_leaderboard_entry(&self, user_id: &str) -> LeaderboardEntry {
+            let starting_balance = self
+                .user_stats
+                .get(&user_id.to_string())
+                .expect("User stats not found")
+                .starting_balance;
+            let portfolio_value = self.get_scored_value(user_id);
+            let score = self.scoring_rule.score(
+                starting_balance,
+                &self.get_equity_curve(user_id.to_string()),
+                portfolio_value,
+            );
+
+            LeaderboardEntry {
+                user_id: user_id.to_string(),
+                portfolio_value,
+                score,
+            }
+        }
+
+        /// Gets the achievements a user has earned in this competition.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the user.
+        ///
+        /// # Returns
+        ///
+        /// The list of earned achievements, in the order they were awarded.
+        pub fn get_user_achievements(&self, user_id: String) -> Vec<Achievement> {
+            self.user_stats
+                .get(&user_id)
+                .map(|user_stats| user_stats.achievements.clone())
+                .unwrap_or_default()
         }
     }
 }
@@ -0,0 +1,88 @@
+use scrypto::prelude::*;
+
+/// The highest share of a follower's prize a leader can ask for.
+pub const MAX_PROFIT_SHARE: Decimal = dec!("0.3");
+
+/// A leader's trades are mirrored within their own trade as long as they have at most this many
+/// followers. Trades of leaders with more followers are mirrored by keepers.
+pub const IMMEDIATE_MIRROR_LIMIT: usize = 10;
+
+/// The maximum number of a leader's trades mirrored for one follower in a single call. Longer
+/// backlogs are worked off over several calls.
+pub const MAX_MIRRORED_TRADES_PER_CALL: u64 = 20;
+
+/// The limits a follower opts into when following a leader.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct CopySettings {
+    /// The share of the proportional trade size to copy, e.g. 0.5 to copy half as much.
+    pub copy_ratio: Decimal,
+    /// The maximum FUSD value of a single mirrored trade.
+    pub max_trade_value: Option<Decimal>,
+    /// The highest profit share of the leader the follower accepts.
+    pub max_profit_share: Decimal,
+}
+
+impl CopySettings {
+    /// Asserts that the limits are within their valid ranges.
+    pub fn assert_valid(&self) {
+        assert!(
+            self.copy_ratio.is_positive() && self.copy_ratio <= Decimal::one(),
+            "The copy ratio must be above 0 and at most 1."
+        );
+        if let Some(max_trade_value) = self.max_trade_value {
+            assert!(
+                max_trade_value.is_positive(),
+                "The maximum trade value must be positive."
+            );
+        }
+        assert!(
+            !self.max_profit_share.is_negative(),
+            "The maximum profit share cannot be negative."
+        );
+    }
+}
+
+/// A follower's subscription to the trades of a leader.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct Follow {
+    pub leader_id: String,
+    pub settings: CopySettings,
+    /// The share of the follower's prize the leader receives, fixed when following.
+    pub profit_share: Decimal,
+    /// The index of the leader's next copy trade to mirror.
+    pub next_copy_index: u64,
+}
+
+/// A leader's trade in a form that can be mirrored by portfolios of any size.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct CopyTrade {
+    pub from_address: ResourceAddress,
+    pub to_address: ResourceAddress,
+    /// The FUSD value of the trade relative to the leader's portfolio value before the trade.
+    pub portfolio_share: Decimal,
+}
+
+impl Follow {
+    /// Calculates the FUSD value of a mirrored trade.
+    ///
+    /// # Arguments
+    ///
+    /// * `copy_trade` - The leader's trade.
+    /// * `portfolio_value` - The FUSD value of the follower's portfolio.
+    ///
+    /// # Returns
+    ///
+    /// The value in proportion to the follower's portfolio, reduced by the copy ratio and
+    /// bounded by the maximum trade value.
+    pub fn mirrored_trade_value(
+        &self,
+        copy_trade: &CopyTrade,
+        portfolio_value: Decimal,
+    ) -> Decimal {
+        let trade_value = copy_trade.portfolio_share * portfolio_value * self.settings.copy_ratio;
+        match self.settings.max_trade_value {
+            Some(max_trade_value) => trade_value.min(max_trade_value),
+            None => trade_value,
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use crate::achievement::Achievement;
+use crate::competition::TradeRecord;
+use crate::keeper::KeeperAction;
+use crate::options::OptionKind;
+use crate::perpetuals::PositionSide;
+use crate::scheduled_orders::ScheduledOrder;
+use scrypto::prelude::*;
+
+/// Emitted by `Competition` when a user registers and receives the starting balance.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct UserRegisteredEvent {
+    pub user_id: String,
+    pub starting_balance: Decimal,
+}
+
+/// Emitted by `Competition` when a user leaves the competition before it starts.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct UserUnregisteredEvent {
+    pub user_id: String,
+}
+
+/// Emitted by `Competition` after a user's trade has been executed.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct TradeExecutedEvent {
+    pub user_id: String,
+    pub trade_index: u64,
+    pub trade: TradeRecord,
+}
+
+/// Emitted by `Competition` when a user opens a perpetual position.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct PerpetualPositionOpenedEvent {
+    pub user_id: String,
+    pub resource_address: ResourceAddress,
+    pub side: PositionSide,
+    pub size: Decimal,
+    pub entry_price: Decimal,
+    pub margin: Decimal,
+}
+
+/// Emitted by `Competition` when a perpetual position is closed by its user or liquidated.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct PerpetualPositionClosedEvent {
+    pub user_id: String,
+    pub resource_address: ResourceAddress,
+    pub realized_pnl: Decimal,
+    pub liquidated: bool,
+}
+
+/// Emitted by `Competition` when a user buys an option.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct OptionBoughtEvent {
+    pub user_id: String,
+    pub option_id: NonFungibleLocalId,
+    pub underlying: ResourceAddress,
+    pub kind: OptionKind,
+    pub strike: Decimal,
+    pub expiry: Instant,
+    pub quantity: Decimal,
+    pub premium: Decimal,
+}
+
+/// Emitted by `Competition` when an expired option is cash-settled.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct OptionSettledEvent {
+    pub user_id: String,
+    pub option_id: NonFungibleLocalId,
+    pub settlement_price: Decimal,
+    pub payoff: Decimal,
+}
+
+/// Emitted by `Competition` when a user schedules a recurring or sliced order.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct OrderScheduledEvent {
+    pub order_id: u64,
+    pub order: ScheduledOrder,
+}
+
+/// Emitted by `Competition` when a keeper executes a slice of a scheduled order.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct ScheduledOrderExecutedEvent {
+    pub order_id: u64,
+    pub user_id: String,
+    pub from_amount: Decimal,
+    pub completed: bool,
+}
+
+/// Emitted by `Competition` when a user cancels a scheduled order.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct ScheduledOrderCancelledEvent {
+    pub order_id: u64,
+    pub user_id: String,
+}
+
+/// Emitted by `Competition` when XRD is added to the keeper rewards.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct KeeperRewardsFundedEvent {
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when a keeper is rewarded for executing an action.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct KeeperRewardPaidEvent {
+    pub action: KeeperAction,
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when a user starts copying a leader.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct LeaderFollowedEvent {
+    pub user_id: String,
+    pub leader_id: String,
+    pub profit_share: Decimal,
+}
+
+/// Emitted by `Competition` when a user stops copying a leader.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct LeaderUnfollowedEvent {
+    pub user_id: String,
+    pub leader_id: String,
+}
+
+/// Emitted by `Competition` when a leader's trade is mirrored into a follower's vault.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct TradeMirroredEvent {
+    pub user_id: String,
+    pub leader_id: String,
+    pub copy_index: u64,
+}
+
+/// Emitted by `Competition` when a share of a follower's prize is assigned to the leader.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct ProfitSharePaidEvent {
+    pub user_id: String,
+    pub leader_id: String,
+    pub resource_address: ResourceAddress,
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when a spectator stakes XRD on a player.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct BetPlacedEvent {
+    pub user_id: String,
+    pub player_id: String,
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when a spectator claims their winnings or refunds.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct BetWinningsClaimedEvent {
+    pub user_id: String,
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when a user registers with a referrer.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct ReferralRecordedEvent {
+    pub user_id: String,
+    pub referrer_id: String,
+    pub fee: Decimal,
+    pub bonus_balance: Decimal,
+}
+
+/// Emitted by `Competition` when a referrer withdraws their share of entry fees.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct ReferralFeesClaimedEvent {
+    pub referrer_id: String,
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when a user commits to a trade in the commit-reveal trade mode.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct TradeCommittedEvent {
+    pub user_id: String,
+    pub commitment: Hash,
+}
+
+/// Emitted by `Competition` when the registration window or the competition times change.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct CompetitionTimeChangedEvent {
+    pub registration_start: Instant,
+    pub registration_end: Instant,
+    pub competition_start: Instant,
+    pub competition_end: Instant,
+}
+
+/// Emitted by `Competition` when the portfolios of all participants have been valued.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct SnapshotTakenEvent {
+    pub timestamp: Instant,
+    pub participant_count: u64,
+}
+
+/// Emitted by `Competition` when an achievement badge is minted for a user.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct AchievementAwardedEvent {
+    pub user_id: String,
+    pub achievement: Achievement,
+    pub badge_id: NonFungibleLocalId,
+}
+
+/// Emitted by `Competition` when the final ranking is fixed and the prizes are assigned.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct CompetitionSettledEvent {
+    pub winners: Vec<String>,
+}
+
+/// Emitted by `Competition` when the admin cancels the competition.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct CompetitionCancelledEvent {
+    pub participant_count: u64,
+}
+
+/// Emitted by `Competition` when the admin adds a sponsor.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct SponsorAddedEvent {
+    pub sponsor_id: u64,
+    pub name: String,
+}
+
+/// Emitted by `Competition` when tokens are deposited into the prize pools for a sponsor.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct PrizePoolSponsoredEvent {
+    pub sponsor_id: u64,
+    pub resource_address: ResourceAddress,
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when a winner withdraws a prize.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct PrizeClaimedEvent {
+    pub user_id: String,
+    pub resource_address: ResourceAddress,
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when the holder of a prize claim withdraws unlocked tokens.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct VestedPrizeWithdrawnEvent {
+    pub claim_id: NonFungibleLocalId,
+    pub resource_address: ResourceAddress,
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when a user's entry fee is paid back.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct EntryFeeRefundedEvent {
+    pub user_id: String,
+    pub amount: Decimal,
+}
+
+/// Emitted by `Competition` when it creates its own version of a reference asset.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct VirtualAssetCreatedEvent {
+    pub resource_address: ResourceAddress,
+    pub reference_address: ResourceAddress,
+}
+
+/// Emitted by `TradeSimulator` when a resource is whitelisted for trading.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct ResourceWhitelistedEvent {
+    pub resource_address: ResourceAddress,
+}
+
+/// Emitted by `SimpleOracle` when the price of a resource is set.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct PriceUpdatedEvent {
+    pub resource_address: ResourceAddress,
+    pub price: Decimal,
+}
+
+/// Emitted by `Season` when it creates a competition.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct SeasonCompetitionCreatedEvent {
+    pub competition_address: ComponentAddress,
+}
+
+/// Emitted by `Season` when a settled competition reports its results.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct SeasonResultsReportedEvent {
+    pub competition_address: ComponentAddress,
+    pub points_awarded: Vec<(String, u64)>,
+}
+
+/// Emitted by `Season` when the season leaderboard is fixed and the prize pool is assigned.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct SeasonEndedEvent {
+    pub winners: Vec<String>,
+}
+
+/// Emitted by `Season` when a player withdraws a season prize.
+#[derive(ScryptoSbor, ScryptoEvent)]
+pub struct SeasonPrizeClaimedEvent {
+    pub user_id: String,
+    pub amount: Decimal,
+}
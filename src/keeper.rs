@@ -0,0 +1,77 @@
+use scrypto::prelude::*;
+
+/// A time-critical action that anyone can execute and be rewarded for.
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeeperAction {
+    Snapshot,
+    Liquidation,
+    ScheduledOrderSlice,
+    OptionSettlement,
+    TradeMirroring,
+}
+
+/// The XRD bounty paid to keepers and the rules against farming it, set by the admin.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct KeeperRewardConfig {
+    /// The XRD paid for each executed action, zero to pay nothing.
+    pub reward: Decimal,
+    /// The shortest interval of a scheduled order whose slices are rewarded, in seconds. Keeps
+    /// players from farming rewards with tiny, frequent orders.
+    pub min_rewarded_order_interval: i64,
+    /// The minimum FUSD value of an action on behalf of a player for it to be rewarded: the
+    /// margin of a liquidated position, the premium of a settled option or the value of an
+    /// order slice or of mirrored trades.
+    pub min_rewarded_action_value: Decimal,
+    /// The maximum number of rewards paid per trading day across all keepers.
+    pub max_rewards_per_day: Option<u64>,
+}
+
+impl Default for KeeperRewardConfig {
+    fn default() -> Self {
+        Self {
+            reward: Decimal::zero(),
+            min_rewarded_order_interval: 60 * 60,
+            min_rewarded_action_value: dec!(100),
+            max_rewards_per_day: Some(100),
+        }
+    }
+}
+
+impl KeeperRewardConfig {
+    /// Asserts that the parameters are within their valid ranges.
+    pub fn assert_valid(&self) {
+        assert!(
+            !self.reward.is_negative(),
+            "The keeper reward cannot be negative."
+        );
+        assert!(
+            self.min_rewarded_order_interval >= 0,
+            "The minimum rewarded order interval cannot be negative."
+        );
+        assert!(
+            !self.min_rewarded_action_value.is_negative(),
+            "The minimum rewarded action value cannot be negative."
+        );
+    }
+
+    /// Checks whether another reward can be paid on a trading day.
+    ///
+    /// # Arguments
+    ///
+    /// * `rewards_today` - The number of rewards already paid on the trading day.
+    pub fn allows_reward(&self, rewards_today: u64) -> bool {
+        self.reward.is_positive()
+            && self
+                .max_rewards_per_day
+                .is_none_or(|max_rewards_per_day| rewards_today < max_rewards_per_day)
+    }
+
+    /// Checks whether an action on behalf of a player is large enough to be rewarded.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_value` - The FUSD value of the action.
+    pub fn rewards_action_value(&self, action_value: Decimal) -> bool {
+        action_value >= self.min_rewarded_action_value
+    }
+}
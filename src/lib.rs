@@ -1,4 +1,18 @@
+pub mod achievement;
+pub mod betting;
 pub mod competition;
+pub mod copy_trading;
+pub mod events;
+pub mod keeper;
+pub mod options;
 pub mod oracle;
+pub mod perpetuals;
+pub mod referral;
+pub mod risk;
+pub mod scheduled_orders;
+pub mod scoring;
+pub mod season;
+pub mod sponsorship;
 pub mod trade_simulator;
 pub mod user_asset_vault;
+pub mod vesting;
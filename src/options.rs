@@ -0,0 +1,196 @@
+use scrypto::prelude::*;
+
+/// The number of seconds in a year, used to express the time to expiry in years.
+pub const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+
+/// The maximum number of unsettled options per player.
+pub const MAX_OPEN_OPTIONS: usize = 10;
+
+const LN_2: Decimal = dec!("0.693147180559945309");
+const INV_SQRT_2_PI: Decimal = dec!("0.398942280401432678");
+const SERIES_TERMS: u64 = 40;
+
+/// The right an option gives its holder.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptionKind {
+    /// Pays the amount the price ends above the strike.
+    Call,
+    /// Pays the amount the price ends below the strike.
+    Put,
+}
+
+/// The non-fungible data of a European option held in a player's vault.
+#[derive(ScryptoSbor, NonFungibleData, Clone, Debug, PartialEq, Eq)]
+pub struct OptionContract {
+    /// The virtual asset the option is written on.
+    pub underlying: ResourceAddress,
+    pub kind: OptionKind,
+    /// The FUSD price the payoff is measured against.
+    pub strike: Decimal,
+    /// The time the option is cash-settled at.
+    pub expiry: Instant,
+    /// The number of units of the underlying the option covers.
+    pub quantity: Decimal,
+    /// The FUSD paid for the option.
+    pub premium: Decimal,
+}
+
+impl OptionContract {
+    /// Calculates the FUSD the option pays if it is settled at a price.
+    pub fn payoff(&self, spot: Decimal) -> Decimal {
+        let payoff_per_unit = match self.kind {
+            OptionKind::Call => spot - self.strike,
+            OptionKind::Put => self.strike - spot,
+        };
+        payoff_per_unit.max(Decimal::zero()) * self.quantity
+    }
+
+    /// Values the option with Black-Scholes, and at its payoff once it has expired.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - The FUSD price of the underlying, the price at expiry once the option has
+    ///   expired.
+    /// * `volatility` - The annualized implied volatility of the underlying, e.g. 0.8 for 80%.
+    /// * `now` - The current time.
+    pub fn value(&self, spot: Decimal, volatility: Decimal, now: Instant) -> Decimal {
+        let seconds_to_expiry = self.expiry.seconds_since_unix_epoch - now.seconds_since_unix_epoch;
+        if seconds_to_expiry <= 0 {
+            return self.payoff(spot);
+        }
+        let time_to_expiry = Decimal::from(seconds_to_expiry) / Decimal::from(SECONDS_PER_YEAR);
+
+        black_scholes_price(self.kind, spot, self.strike, volatility, time_to_expiry)
+            * self.quantity
+    }
+}
+
+/// An option held by a player, valued at the current oracle price.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct OptionPositionValue {
+    pub id: NonFungibleLocalId,
+    pub contract: OptionContract,
+    pub value: Decimal,
+}
+
+/// Prices one unit of a European option with the Black-Scholes formula. The interest rate is
+/// zero, as FUSD does not earn interest in the game.
+///
+/// # Arguments
+///
+/// * `kind` - Whether the option is a call or a put.
+/// * `spot` - The current price of the underlying.
+/// * `strike` - The strike price of the option.
+/// * `volatility` - The annualized implied volatility, e.g. 0.8 for 80%.
+/// * `time_to_expiry` - The time until expiry in years.
+///
+/// # Returns
+///
+/// The price of the option, never negative.
+pub fn black_scholes_price(
+    kind: OptionKind,
+    spot: Decimal,
+    strike: Decimal,
+    volatility: Decimal,
+    time_to_expiry: Decimal,
+) -> Decimal {
+    let deviation = volatility * time_to_expiry.checked_sqrt().expect("Square root error");
+    if !deviation.is_positive() {
+        let intrinsic_value = match kind {
+            OptionKind::Call => spot - strike,
+            OptionKind::Put => strike - spot,
+        };
+        return intrinsic_value.max(Decimal::zero());
+    }
+
+    let d1 = (ln(spot.checked_div(strike).expect("Division by zero error"))
+        + volatility * volatility * time_to_expiry / dec!(2))
+        / deviation;
+    let d2 = d1 - deviation;
+
+    let price = match kind {
+        OptionKind::Call => spot * normal_cdf(d1) - strike * normal_cdf(d2),
+        OptionKind::Put => strike * normal_cdf(-d2) - spot * normal_cdf(-d1),
+    };
+    price.max(Decimal::zero())
+}
+
+/// Approximates the cumulative distribution function of the standard normal distribution with
+/// the Abramowitz and Stegun formula 26.2.17, which is accurate to 7.5e-8.
+pub fn normal_cdf(x: Decimal) -> Decimal {
+    if x.is_negative() {
+        return Decimal::one() - normal_cdf(-x);
+    }
+
+    let t = Decimal::one() / (Decimal::one() + dec!("0.2316419") * x);
+    let polynomial = t
+        * (dec!("0.319381530")
+            + t * (dec!("-0.356563782")
+                + t * (dec!("1.781477937")
+                    + t * (dec!("-1.821255978") + t * dec!("1.330274429")))));
+    let density = INV_SQRT_2_PI * exp(-(x * x) / dec!(2));
+
+    Decimal::one() - density * polynomial
+}
+
+/// Approximates the natural logarithm of a positive number.
+pub fn ln(x: Decimal) -> Decimal {
+    assert!(
+        x.is_positive(),
+        "The logarithm is only defined for positive numbers."
+    );
+
+    // Scale into [1, 2) so that the series converges quickly
+    let mut mantissa = x;
+    let mut exponent: i64 = 0;
+    while mantissa >= dec!(2) {
+        mantissa /= dec!(2);
+        exponent += 1;
+    }
+    while mantissa < Decimal::one() {
+        mantissa *= dec!(2);
+        exponent -= 1;
+    }
+
+    // ln(m) = 2 * (z + z^3 / 3 + z^5 / 5 + ...) with z = (m - 1) / (m + 1)
+    let z = (mantissa - Decimal::one()) / (mantissa + Decimal::one());
+    let z_squared = z * z;
+    let mut power = z;
+    let mut sum = Decimal::zero();
+    for n in 0..SERIES_TERMS {
+        let term = power / Decimal::from(2 * n + 1);
+        if term.is_zero() {
+            break;
+        }
+        sum += term;
+        power *= z_squared;
+    }
+
+    sum * dec!(2) + LN_2 * Decimal::from(exponent)
+}
+
+/// Approximates the exponential function.
+pub fn exp(x: Decimal) -> Decimal {
+    // Halve until the Taylor series converges quickly, then square the result back up
+    let mut reduced = x;
+    let mut halvings = 0;
+    while reduced.checked_abs().expect("Overflow") > dec!("0.5") {
+        reduced /= dec!(2);
+        halvings += 1;
+    }
+
+    let mut term = Decimal::one();
+    let mut sum = Decimal::one();
+    for n in 1..=SERIES_TERMS {
+        term = term * reduced / Decimal::from(n);
+        if term.is_zero() {
+            break;
+        }
+        sum += term;
+    }
+
+    for _ in 0..halvings {
+        sum = sum.checked_mul(sum).expect("Multiplication overflow");
+    }
+    sum
+}
@@ -1,6 +1,8 @@
+use crate::events::PriceUpdatedEvent;
 use scrypto::prelude::*;
 
 #[blueprint]
+#[events(PriceUpdatedEvent)]
 mod simple_oracle {
     enable_method_auth! {
         roles {
@@ -9,11 +11,16 @@ mod simple_oracle {
         methods {
             set_price => restrict_to: [oracle_manager];
             get_price => PUBLIC;
+            get_last_update => PUBLIC;
+            get_price_at => PUBLIC;
         }
     }
 
     pub struct SimpleOracle {
         prices: KeyValueStore<ResourceAddress, Decimal>, // Stores the price of each resource.
+        last_updates: KeyValueStore<ResourceAddress, Instant>, // When each price was last set.
+        price_history: KeyValueStore<(ResourceAddress, u64), (Instant, Decimal)>, // Every price set for each resource, oldest first.
+        update_counts: KeyValueStore<ResourceAddress, u64>, // The number of prices set for each resource.
     }
 
     impl SimpleOracle {
@@ -41,6 +48,9 @@ mod simple_oracle {
 
             Self {
                 prices: KeyValueStore::new(),
+                last_updates: KeyValueStore::new(),
+                price_history: KeyValueStore::new(),
+                update_counts: KeyValueStore::new(),
             }
             .instantiate()
             .prepare_to_globalize(owner_role)
@@ -62,7 +72,19 @@ mod simple_oracle {
         /// * `address` - The resource address for which the price is to be set.
         /// * `price` - The price of the resource.
         pub fn set_price(&mut self, address: ResourceAddress, price: Decimal) {
-            self.prices.insert(address, price)
+            let now = Clock::current_time(TimePrecisionV2::Second);
+            self.prices.insert(address, price);
+            self.last_updates.insert(address, now);
+
+            let update_count = self.update_counts.get(&address).map_or(0, |count| *count);
+            self.price_history
+                .insert((address, update_count), (now, price));
+            self.update_counts.insert(address, update_count + 1);
+
+            Runtime::emit_event(PriceUpdatedEvent {
+                resource_address: address,
+                price,
+            });
         }
 
         /// Gets the price for a given resource address.
@@ -81,5 +103,60 @@ mod simple_oracle {
                 .expect("Price not found for this resource");
             price
         }
+
+        /// Gets the price a given resource address had at a point in time.
+        ///
+        /// # Arguments
+        ///
+        /// * `address` - The resource address for which the price is to be retrieved.
+        /// * `time` - The point in time.
+        ///
+        /// # Returns
+        ///
+        /// The latest price set at or before the given time.
+        pub fn get_price_at(&self, address: ResourceAddress, time: Instant) -> Decimal {
+            let update_count = *self
+                .update_counts
+                .get(&address)
+                .expect("Price not found for this resource");
+
+            // Find the first update after the given time
+            let (mut low, mut high) = (0, update_count);
+            while low < high {
+                let middle = low + (high - low) / 2;
+                let (timestamp, _) = *self
+                    .price_history
+                    .get(&(address, middle))
+                    .expect("Price update not found");
+                if timestamp <= time {
+                    low = middle + 1;
+                } else {
+                    high = middle;
+                }
+            }
+            assert!(low > 0, "No price was set for this resource at that time.");
+
+            let (_, price) = *self
+                .price_history
+                .get(&(address, low - 1))
+                .expect("Price update not found");
+            price
+        }
+
+        /// Gets the time the price of a given resource address was last set.
+        ///
+        /// # Arguments
+        ///
+        /// * `address` - The resource address of the price.
+        ///
+        /// # Returns
+        ///
+        /// The time of the latest price update.
+        pub fn get_last_update(&self, address: ResourceAddress) -> Instant {
+            *self
+                .last_updates
+                .get(&address)
+                .expect("Price not found for this resource")
+        }
     }
 }
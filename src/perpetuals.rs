@@ -0,0 +1,195 @@
+use scrypto::prelude::*;
+
+/// The number of seconds between two funding payments of a perpetual market.
+pub const FUNDING_INTERVAL: i64 = 60 * 60;
+
+/// The direction of a perpetual position.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionSide {
+    /// Profits when the price rises.
+    Long,
+    /// Profits when the price falls.
+    Short,
+}
+
+/// The parameters of a perpetual market, set by the admin.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PerpetualMarketConfig {
+    /// The highest leverage a position can be opened with, e.g. 10 for 10x.
+    pub max_leverage: Decimal,
+    /// The funding rate per interval if all open interest is on one side, e.g. 0.001 for 0.1%.
+    pub max_funding_rate: Decimal,
+    /// The share of the position value a position needs as equity to not be liquidated.
+    pub maintenance_margin: Decimal,
+}
+
+impl PerpetualMarketConfig {
+    /// Asserts that the parameters are within their valid ranges.
+    pub fn assert_valid(&self) {
+        assert!(
+            self.max_leverage >= Decimal::one(),
+            "The maximum leverage must be at least 1."
+        );
+        assert!(
+            !self.max_funding_rate.is_negative(),
+            "The maximum funding rate cannot be negative."
+        );
+        assert!(
+            !self.maintenance_margin.is_negative() && self.maintenance_margin < Decimal::one(),
+            "The maintenance margin must be at least 0 and below 1."
+        );
+    }
+}
+
+/// The state of a perpetual market on a single virtual asset.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PerpetualMarket {
+    pub config: PerpetualMarketConfig,
+    /// The total size of all long positions in units of the asset.
+    pub long_open_interest: Decimal,
+    /// The total size of all short positions in units of the asset.
+    pub short_open_interest: Decimal,
+    /// The FUSD a long position of one unit has paid in funding since the market opened.
+    pub funding_index: Decimal,
+    /// The time up to which funding has been applied.
+    pub last_funding: Instant,
+}
+
+impl PerpetualMarket {
+    /// Creates a market without open interest.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The parameters of the market.
+    /// * `now` - The time funding starts to accrue from.
+    pub fn new(config: PerpetualMarketConfig, now: Instant) -> Self {
+        Self {
+            config,
+            long_open_interest: Decimal::zero(),
+            short_open_interest: Decimal::zero(),
+            funding_index: Decimal::zero(),
+            last_funding: now,
+        }
+    }
+
+    /// Calculates the funding rate per interval from the open interest imbalance. Longs pay
+    /// shorts if the rate is positive, shorts pay longs if it is negative.
+    pub fn funding_rate(&self) -> Decimal {
+        let total_open_interest = self.long_open_interest + self.short_open_interest;
+        if total_open_interest.is_zero() {
+            return Decimal::zero();
+        }
+        self.config.max_funding_rate * (self.long_open_interest - self.short_open_interest)
+            / total_open_interest
+    }
+
+    /// Calculates the funding index including the funding intervals that have passed but have
+    /// not been applied yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `mark_price` - The current FUSD price of the asset.
+    /// * `now` - The current time.
+    pub fn current_funding_index(&self, mark_price: Decimal, now: Instant) -> Decimal {
+        let intervals = self.elapsed_intervals(now);
+        self.funding_index + self.funding_rate() * mark_price * intervals
+    }
+
+    /// Applies the funding of all funding intervals that have passed. Must be called before the
+    /// open interest changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `mark_price` - The current FUSD price of the asset.
+    /// * `now` - The current time.
+    pub fn apply_funding(&mut self, mark_price: Decimal, now: Instant) {
+        let intervals = self.elapsed_intervals(now);
+        self.funding_index = self.current_funding_index(mark_price, now);
+        self.last_funding =
+            Instant::new(self.last_funding.seconds_since_unix_epoch + intervals * FUNDING_INTERVAL);
+    }
+
+    /// Adds or removes the size of a position from the open interest.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side of the position.
+    /// * `size` - The size to add, negative to remove.
+    pub fn change_open_interest(&mut self, side: PositionSide, size: Decimal) {
+        match side {
+            PositionSide::Long => self.long_open_interest += size,
+            PositionSide::Short => self.short_open_interest += size,
+        }
+    }
+
+    fn elapsed_intervals(&self, now: Instant) -> i64 {
+        (now.seconds_since_unix_epoch - self.last_funding.seconds_since_unix_epoch).max(0)
+            / FUNDING_INTERVAL
+    }
+}
+
+/// An open perpetual position of a player.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PerpetualPosition {
+    pub side: PositionSide,
+    /// The size in units of the asset.
+    pub size: Decimal,
+    /// The FUSD price the position was opened at.
+    pub entry_price: Decimal,
+    /// The FUSD put up as collateral.
+    pub margin: Decimal,
+    /// The funding index of the market when the position was opened.
+    pub entry_funding_index: Decimal,
+}
+
+impl PerpetualPosition {
+    /// Calculates the profit or loss of the position at a price, excluding funding.
+    pub fn unrealized_pnl(&self, mark_price: Decimal) -> Decimal {
+        match self.side {
+            PositionSide::Long => self.size * (mark_price - self.entry_price),
+            PositionSide::Short => self.size * (self.entry_price - mark_price),
+        }
+    }
+
+    /// Calculates the funding the position has paid since it was opened. Negative if the
+    /// position has received funding.
+    pub fn funding_paid(&self, funding_index: Decimal) -> Decimal {
+        let long_funding = self.size * (funding_index - self.entry_funding_index);
+        match self.side {
+            PositionSide::Long => long_funding,
+            PositionSide::Short => -long_funding,
+        }
+    }
+
+    /// Calculates the FUSD value of the position: its margin plus profit and minus funding.
+    pub fn equity(&self, mark_price: Decimal, funding_index: Decimal) -> Decimal {
+        self.margin + self.unrealized_pnl(mark_price) - self.funding_paid(funding_index)
+    }
+
+    /// Checks whether the equity of the position has fallen below the maintenance margin.
+    ///
+    /// # Arguments
+    ///
+    /// * `mark_price` - The current FUSD price of the asset.
+    /// * `funding_index` - The current funding index of the market.
+    /// * `maintenance_margin` - The share of the position value required as equity.
+    pub fn is_liquidatable(
+        &self,
+        mark_price: Decimal,
+        funding_index: Decimal,
+        maintenance_margin: Decimal,
+    ) -> bool {
+        self.equity(mark_price, funding_index) < self.size * mark_price * maintenance_margin
+    }
+}
+
+/// An open perpetual position valued at the current mark price.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PerpetualPositionValue {
+    pub resource_address: ResourceAddress,
+    pub position: PerpetualPosition,
+    pub mark_price: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub funding_paid: Decimal,
+    pub equity: Decimal,
+}
@@ -0,0 +1,38 @@
+use scrypto::prelude::*;
+
+/// The rewards a referrer earns for each referred player, set by the admin.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReferralConfig {
+    /// The share of the referred player's entry fee paid to the referrer, e.g. 0.1 for 10%.
+    pub fee_share: Decimal,
+    /// The FUSD credited to the referrer's portfolio, if the referrer is registered. It does not
+    /// count towards the referrer's score.
+    pub bonus_balance: Decimal,
+}
+
+impl ReferralConfig {
+    /// Asserts that the rewards are within their valid ranges.
+    pub fn assert_valid(&self) {
+        assert!(
+            !self.fee_share.is_negative() && self.fee_share <= Decimal::one(),
+            "The fee share must be between 0 and 1."
+        );
+        assert!(
+            !self.bonus_balance.is_negative(),
+            "The bonus balance cannot be negative."
+        );
+    }
+}
+
+/// What a referrer has earned in a competition.
+#[derive(ScryptoSbor, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReferrerStats {
+    /// The number of players who named the user as their referrer.
+    pub referral_count: u64,
+    /// The entry fees earned from referred players who are still registered.
+    pub fees_earned: Decimal,
+    /// The entry fees already withdrawn.
+    pub fees_claimed: Decimal,
+    /// The FUSD credited to the referrer's portfolio.
+    pub bonus_balance: Decimal,
+}
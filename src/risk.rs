@@ -0,0 +1,216 @@
+use scrypto::prelude::*;
+
+/// The length of a trading day in seconds. Trading days are counted from the competition start.
+pub const SECONDS_PER_TRADING_DAY: i64 = 24 * 60 * 60;
+
+/// The time in seconds after the reveal delay during which a committed trade can be revealed.
+/// Later, the commitment expires so that a player cannot wait for a favourable price.
+pub const REVEAL_WINDOW: i64 = 5 * 60;
+
+/// The limits a competition puts on its players' trades. Every limit is optional.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RiskRules {
+    /// The maximum share of the portfolio value held in any asset other than FUSD, e.g. 0.25
+    /// for 25%.
+    pub max_position_share: Option<Decimal>,
+    /// The minimum FUSD value of a trade.
+    pub min_trade_value: Option<Decimal>,
+    /// The maximum number of trades a player can execute per trading day.
+    pub max_trades_per_day: Option<u64>,
+}
+
+impl RiskRules {
+    /// Asserts that the limits are within their valid ranges.
+    pub fn assert_valid(&self) {
+        if let Some(max_position_share) = self.max_position_share {
+            assert!(
+                max_position_share.is_positive() && max_position_share <= Decimal::one(),
+                "The maximum position share must be above 0 and at most 1."
+            );
+        }
+        if let Some(min_trade_value) = self.min_trade_value {
+            assert!(
+                !min_trade_value.is_negative(),
+                "The minimum trade value cannot be negative."
+            );
+        }
+        if let Some(max_trades_per_day) = self.max_trades_per_day {
+            assert!(
+                max_trades_per_day > 0,
+                "The maximum number of trades per day must be positive."
+            );
+        }
+    }
+
+    /// Checks whether a trade is large enough.
+    ///
+    /// # Arguments
+    ///
+    /// * `trade_value` - The FUSD value of the traded amount.
+    pub fn allows_trade_value(&self, trade_value: Decimal) -> bool {
+        self.min_trade_value
+            .is_none_or(|min_trade_value| trade_value >= min_trade_value)
+    }
+
+    /// Asserts that a trade is large enough.
+    ///
+    /// # Arguments
+    ///
+    /// * `trade_value` - The FUSD value of the traded amount.
+    pub fn assert_trade_value(&self, trade_value: Decimal) {
+        if let Some(min_trade_value) = self.min_trade_value {
+            assert!(
+                trade_value >= min_trade_value,
+                "The trade is below the minimum trade value of {} FUSD.",
+                min_trade_value
+            );
+        }
+    }
+
+    /// Asserts that a player has not used up the trades of the current trading day.
+    ///
+    /// # Arguments
+    ///
+    /// * `trades_today` - The number of trades including the one being executed.
+    pub fn assert_trade_count(&self, trades_today: u64) {
+        if let Some(max_trades_per_day) = self.max_trades_per_day {
+            assert!(
+                trades_today <= max_trades_per_day,
+                "The maximum of {} trades per day has been reached.",
+                max_trades_per_day
+            );
+        }
+    }
+
+    /// Checks whether a position stays within the maximum share of the portfolio.
+    ///
+    /// # Arguments
+    ///
+    /// * `position_value` - The FUSD value of the position.
+    /// * `portfolio_value` - The FUSD value of the whole portfolio.
+    pub fn allows_position_share(&self, position_value: Decimal, portfolio_value: Decimal) -> bool {
+        self.max_position_share
+            .is_none_or(|max_position_share| position_value <= portfolio_value * max_position_share)
+    }
+
+    /// Asserts that a position does not make up too much of the portfolio.
+    ///
+    /// # Arguments
+    ///
+    /// * `position_value` - The FUSD value of the position.
+    /// * `portfolio_value` - The FUSD value of the whole portfolio.
+    pub fn assert_position_share(&self, position_value: Decimal, portfolio_value: Decimal) {
+        if let Some(max_position_share) = self.max_position_share {
+            assert!(
+                position_value <= portfolio_value * max_position_share,
+                "The position would exceed {} of the portfolio.",
+                max_position_share
+            );
+        }
+    }
+}
+
+/// The protections of a competition against trading on stale or known-in-advance prices.
+/// Every protection is optional and all durations are in seconds.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TradeProtection {
+    /// The minimum time between two trades of a player.
+    pub cooldown: Option<i64>,
+    /// The minimum time between the latest oracle update of a traded asset and the trade.
+    pub min_price_age: Option<i64>,
+    /// If set, trades must be committed as a hash first and can only be revealed and executed
+    /// this long after the commitment, within the `REVEAL_WINDOW`.
+    pub reveal_delay: Option<i64>,
+}
+
+impl TradeProtection {
+    /// Asserts that no duration is negative.
+    pub fn assert_valid(&self) {
+        for duration in [self.cooldown, self.min_price_age, self.reveal_delay]
+            .into_iter()
+            .flatten()
+        {
+            assert!(duration >= 0, "Durations cannot be negative.");
+        }
+    }
+
+    /// Asserts that enough time has passed since a player's previous trade.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_trade` - The time of the player's previous trade, if any.
+    /// * `now` - The current time.
+    pub fn assert_cooldown_passed(&self, last_trade: Option<Instant>, now: Instant) {
+        if let (Some(cooldown), Some(last_trade)) = (self.cooldown, last_trade) {
+            assert!(
+                now.seconds_since_unix_epoch - last_trade.seconds_since_unix_epoch >= cooldown,
+                "The trade cooldown of {} seconds has not passed yet.",
+                cooldown
+            );
+        }
+    }
+
+    /// Asserts that a committed trade can be revealed now, which is after the reveal delay and
+    /// before the commitment expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `committed_at` - The time of the commitment.
+    /// * `now` - The current time.
+    pub fn assert_revealable(&self, committed_at: Instant, now: Instant) {
+        let reveal_delay = self
+            .reveal_delay
+            .expect("Trades do not need to be committed in this competition.");
+        let elapsed = now.seconds_since_unix_epoch - committed_at.seconds_since_unix_epoch;
+        assert!(
+            elapsed >= reveal_delay,
+            "The trade can only be revealed {} seconds after the commitment.",
+            reveal_delay
+        );
+        assert!(
+            elapsed <= reveal_delay + REVEAL_WINDOW,
+            "The commitment has expired."
+        );
+    }
+
+    /// Asserts that a price has not been updated too recently.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_update` - The time of the latest oracle update of the price.
+    /// * `now` - The current time.
+    pub fn assert_price_settled(&self, last_update: Instant, now: Instant) {
+        if let Some(min_price_age) = self.min_price_age {
+            assert!(
+                now.seconds_since_unix_epoch - last_update.seconds_since_unix_epoch
+                    >= min_price_age,
+                "The price was updated less than {} seconds ago.",
+                min_price_age
+            );
+        }
+    }
+}
+
+/// Computes the commitment of a trade for the commit-reveal trade mode.
+///
+/// # Arguments
+///
+/// * `from_address` - The resource address of the asset to sell.
+/// * `to_address` - The resource address of the asset to buy.
+/// * `amount` - The amount to sell.
+/// * `salt` - A secret chosen by the player so that the commitment cannot be guessed.
+///
+/// # Returns
+///
+/// The hash of the SBOR encoded trade.
+pub fn trade_commitment(
+    from_address: ResourceAddress,
+    to_address: ResourceAddress,
+    amount: Decimal,
+    salt: &str,
+) -> Hash {
+    hash(
+        scrypto_encode(&(from_address, to_address, amount, salt.to_string()))
+            .expect("Failed to encode the trade"),
+    )
+}
@@ -0,0 +1,154 @@
+use scrypto::prelude::*;
+
+/// The maximum number of open scheduled orders per player.
+pub const MAX_OPEN_ORDERS: usize = 10;
+
+/// How a scheduled order splits its trades.
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleKind {
+    /// Buys a fixed amount every interval until the order is cancelled.
+    DollarCostAveraging,
+    /// Splits a total amount into a fixed number of slices.
+    TimeWeightedAveragePrice,
+}
+
+/// A recurring or sliced trade that keepers execute on behalf of a player.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct ScheduledOrder {
+    pub user_id: String,
+    pub kind: ScheduleKind,
+    pub from_address: ResourceAddress,
+    pub to_address: ResourceAddress,
+    /// The amount of the `from_address` asset traded per slice.
+    pub slice_amount: Decimal,
+    /// The number of seconds between two slices.
+    pub interval: i64,
+    /// The time from which the next slice can be executed.
+    pub next_execution: Instant,
+    /// The number of slices left, `None` for orders that run until they are cancelled.
+    pub remaining_slices: Option<u64>,
+}
+
+impl ScheduledOrder {
+    /// Creates an order that trades the same amount every interval, starting now.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user the order trades for.
+    /// * `from_address` - The resource address of the asset to be traded from.
+    /// * `to_address` - The resource address of the asset to be traded to.
+    /// * `amount` - The amount traded per interval.
+    /// * `interval` - The number of seconds between two trades.
+    /// * `now` - The current time.
+    pub fn dollar_cost_averaging(
+        user_id: String,
+        from_address: ResourceAddress,
+        to_address: ResourceAddress,
+        amount: Decimal,
+        interval: i64,
+        now: Instant,
+    ) -> Self {
+        let order = Self {
+            user_id,
+            kind: ScheduleKind::DollarCostAveraging,
+            from_address,
+            to_address,
+            slice_amount: amount,
+            interval,
+            next_execution: now,
+            remaining_slices: None,
+        };
+        order.assert_valid();
+        order
+    }
+
+    /// Creates an order that splits a total amount into equal slices, starting now.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user the order trades for.
+    /// * `from_address` - The resource address of the asset to be traded from.
+    /// * `to_address` - The resource address of the asset to be traded to.
+    /// * `total_amount` - The amount traded over all slices.
+    /// * `slices` - The number of slices.
+    /// * `interval` - The number of seconds between two slices.
+    /// * `now` - The current time.
+    pub fn time_weighted_average_price(
+        user_id: String,
+        from_address: ResourceAddress,
+        to_address: ResourceAddress,
+        total_amount: Decimal,
+        slices: u64,
+        interval: i64,
+        now: Instant,
+    ) -> Self {
+        assert!(slices > 0, "The order needs at least one slice.");
+        let order = Self {
+            user_id,
+            kind: ScheduleKind::TimeWeightedAveragePrice,
+            from_address,
+            to_address,
+            slice_amount: total_amount
+                .checked_div(Decimal::from(slices))
+                .expect("Division by zero error"),
+            interval,
+            next_execution: now,
+            remaining_slices: Some(slices),
+        };
+        order.assert_valid();
+        order
+    }
+
+    /// Asserts that the order trades a positive amount between two different assets.
+    fn assert_valid(&self) {
+        assert!(
+            self.from_address != self.to_address,
+            "An order cannot trade an asset into itself."
+        );
+        assert!(
+            self.slice_amount.is_positive(),
+            "The amount must be positive."
+        );
+        assert!(self.interval > 0, "The interval must be positive.");
+    }
+
+    /// Checks whether the next slice can be executed.
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.next_execution
+    }
+
+    /// Calculates the amount of the next slice, bounded by the player's balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `balance` - The player's balance of the `from_address` asset.
+    pub fn next_slice_amount(&self, balance: Decimal) -> Decimal {
+        self.slice_amount.min(balance)
+    }
+
+    /// Moves the order on to its next slice, which is due one interval after the slice that was
+    /// just executed. A late slice does not make up for missed intervals, so slices are never
+    /// executed back to back.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// Whether the order has executed all of its slices.
+    pub fn advance(&mut self, now: Instant) -> bool {
+        self.next_execution = self
+            .next_execution
+            .max(now)
+            .add_seconds(self.interval)
+            .expect("Time overflow");
+        match self.remaining_slices.as_mut() {
+            Some(remaining_slices) => {
+                *remaining_slices -= 1;
+                *remaining_slices == 0
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::competition::EquityPoint;
+use scrypto::prelude::*;
+
+/// The rule used to rank the players of a competition.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoringRule {
+    /// The highest return on the starting balance wins.
+    HighestRoi,
+    /// The lowest return on the starting balance wins (reverse mode).
+    LowestRoi,
+    /// The best Sharpe-like ratio of the snapshot returns wins.
+    SharpeRatio,
+    /// The smallest peak-to-trough decline of the equity curve wins.
+    LowestMaxDrawdown,
+    /// The highest profit in FUSD wins.
+    AbsolutePnl,
+}
+
+impl ScoringRule {
+    /// Computes the score of a player. A higher score always ranks first.
+    ///
+    /// # Arguments
+    ///
+    /// * `starting_balance` - The FUSD value the player started with.
+    /// * `equity_curve` - The portfolio values recorded by the snapshots.
+    /// * `current_value` - The current FUSD value of the player's portfolio.
+    ///
+    /// # Returns
+    ///
+    /// The score of the player.
+    pub fn score(
+        &self,
+        starting_balance: Decimal,
+        equity_curve: &[EquityPoint],
+        current_value: Decimal,
+    ) -> Decimal {
+        let values: Vec<Decimal> = [starting_balance]
+            .into_iter()
+            .chain(equity_curve.iter().map(|point| point.value))
+            .chain([current_value])
+            .collect();
+
+        match self {
+            ScoringRule::HighestRoi => roi(starting_balance, current_value),
+            ScoringRule::LowestRoi => -roi(starting_balance, current_value),
+            ScoringRule::SharpeRatio => sharpe_ratio(&values),
+            ScoringRule::LowestMaxDrawdown => -max_drawdown(&values),
+            ScoringRule::AbsolutePnl => current_value - starting_balance,
+        }
+    }
+}
+
+/// Calculates the return on investment as a fraction, e.g. 0.5 for a 50% gain.
+pub fn roi(starting_value: Decimal, current_value: Decimal) -> Decimal {
+    if starting_value.is_zero() {
+        return Decimal::zero();
+    }
+    (current_value - starting_value)
+        .checked_div(starting_value)
+        .expect("Division by zero error")
+}
+
+/// Calculates the mean of the period returns divided by their standard deviation.
+///
+/// Returns zero if there are fewer than two returns or the returns do not vary.
+pub fn sharpe_ratio(values: &[Decimal]) -> Decimal {
+    let returns: Vec<Decimal> = values
+        .windows(2)
+        .map(|window| roi(window[0], window[1]))
+        .collect();
+    if returns.len() < 2 {
+        return Decimal::zero();
+    }
+
+    let count = Decimal::from(returns.len() as u64);
+    let mean = returns
+        .iter()
+        .fold(Decimal::zero(), |sum, period_return| sum + *period_return)
+        .checked_div(count)
+        .expect("Division by zero error");
+    let variance = returns
+        .iter()
+        .fold(Decimal::zero(), |sum, period_return| {
+            let deviation = *period_return - mean;
+            sum + deviation * deviation
+        })
+        .checked_div(count)
+        .expect("Division by zero error");
+    let standard_deviation = variance.checked_sqrt().expect("Square root error");
+
+    if standard_deviation.is_zero() {
+        return Decimal::zero();
+    }
+    mean.checked_div(standard_deviation)
+        .expect("Division by zero error")
+}
+
+/// Calculates the largest peak-to-trough decline as a fraction of the peak, e.g. 0.2 for 20%.
+pub fn max_drawdown(values: &[Decimal]) -> Decimal {
+    let mut peak = Decimal::zero();
+    let mut max_drawdown = Decimal::zero();
+
+    for value in values {
+        if *value > peak {
+            peak = *value;
+        } else if peak.is_positive() {
+            let drawdown = (peak - *value)
+                .checked_div(peak)
+                .expect("Division by zero error");
+            max_drawdown = max_drawdown.max(drawdown);
+        }
+    }
+
+    max_drawdown
+}
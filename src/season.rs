@@ -0,0 +1,480 @@
+#![allow(clippy::too_many_arguments)]
+
+use crate::competition::competition::Competition;
+use crate::competition::CompetitionStatus;
+use crate::events::{
+    SeasonCompetitionCreatedEvent, SeasonEndedEvent, SeasonPrizeClaimedEvent,
+    SeasonResultsReportedEvent,
+};
+use scrypto::prelude::*;
+use std::cmp::Reverse;
+
+/// The badge a season hands to each of its competitions. Competitions report their results to
+/// the season with a proof of it.
+#[derive(ScryptoSbor, NonFungibleData)]
+pub struct SeasonBadge {
+    pub season_address: ComponentAddress,
+    pub competition_name: String,
+}
+
+/// A player's position on the season leaderboard.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct SeasonStanding {
+    pub user_id: String,
+    pub points: u64,
+}
+
+#[blueprint]
+#[events(
+    SeasonCompetitionCreatedEvent,
+    SeasonResultsReportedEvent,
+    SeasonEndedEvent,
+    SeasonPrizeClaimedEvent
+)]
+mod season {
+
+    enable_method_auth! {
+        roles {
+            admin => updatable_by: [admin, OWNER];
+        },
+        methods {
+            // Methods with public access
+            report_results => PUBLIC;
+            fund_prize_pool => PUBLIC;
+            claim_prize => PUBLIC;
+            get_name => PUBLIC;
+            get_competitions => PUBLIC;
+            get_competition_badge_address => PUBLIC;
+            get_points_table => PUBLIC;
+            get_payout_table => PUBLIC;
+            get_season_points => PUBLIC;
+            get_leaderboard => PUBLIC;
+            get_prize_pool_amount => PUBLIC;
+            get_prize => PUBLIC;
+            is_ended => PUBLIC;
+
+            // Methods with admin access
+            create_competition => restrict_to: [admin, OWNER];
+            set_points_table => restrict_to: [admin, OWNER];
+            set_payout_table => restrict_to: [admin, OWNER];
+            end_season => restrict_to: [admin, OWNER];
+        }
+    }
+
+    struct Season {
+        name: String,
+        user_token_resource_address: ResourceAddress,
+        admin_badge_address: ResourceAddress,
+        competitions: Vec<Global<Competition>>, // Indexed by the local ID of their season badge.
+        competition_badge_manager: NonFungibleResourceManager,
+        rankings: KeyValueStore<u64, Vec<String>>, // The final ranking reported by each competition.
+        points_table: Vec<u64>, // The season points for each placement, best first.
+        season_points: KeyValueStore<String, u64>,
+        players: Vec<String>, // The keys of `season_points`, in the order they first scored.
+        prize_pool: Vault,
+        payout_table: Vec<Decimal>, // The share of the prize pool for each season rank, best first.
+        final_leaderboard: Vec<SeasonStanding>,
+        prizes: KeyValueStore<String, Decimal>,
+        ended: bool,
+    }
+
+    impl Season {
+        /// Instantiates a new Season component.
+        ///
+        /// # Arguments
+        ///
+        /// * `owner_role` - The owner of the season.
+        /// * `name` - The name of the season, e.g. "Season 3".
+        /// * `user_token_resource_address` - The resource address of user tokens.
+        /// * `prize_resource_address` - The resource of the season-end prize pool.
+        ///
+        /// # Returns
+        ///
+        /// The season and a badge with the authority to manage it.
+        pub fn instantiate(
+            owner_role: OwnerRole,
+            name: String,
+            user_token_resource_address: ResourceAddress,
+            prize_resource_address: ResourceAddress,
+        ) -> (Global<Season>, Bucket) {
+            let (address_reservation, component_address) =
+                Runtime::allocate_component_address(Season::blueprint_id());
+
+            let admin_badge: Bucket = ResourceBuilder::new_fungible(owner_role.clone())
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init {
+                        "name" => "Season Manager", updatable;
+                        "symbol" => "SM", updatable;
+                        "description" => "A badge with the authority to manage the season.", updatable;
+                        "tags" => ["badge"], updatable;
+                        "info_url" => Url::of("https://colosseum.com"), updatable;
+                        "icon_url" => Url::of("https://colosseum.com/images/LogoColosseum.png"), updatable;
+                    }
+                })
+                .mint_initial_supply(1)
+                .into();
+
+            let competition_badge_manager =
+                ResourceBuilder::new_integer_non_fungible::<SeasonBadge>(OwnerRole::None)
+                    .metadata(metadata! {
+                        init {
+                            "name" => format!("Season Competition – {}", name), locked;
+                            "symbol" => "SCB", locked;
+                            "description" => format!("The badge of a competition of the {} season.", name), locked;
+                            "tags" => ["badge"], locked;
+                        }
+                    })
+                    .mint_roles(mint_roles! {
+                        minter => rule!(require(global_caller(component_address)));
+                        minter_updater => rule!(deny_all);
+                    })
+                    .create_with_no_initial_supply();
+
+            let season = Self {
+                name,
+                user_token_resource_address,
+                admin_badge_address: admin_badge.resource_address(),
+                competitions: Vec::new(),
+                competition_badge_manager,
+                rankings: KeyValueStore::new(),
+                points_table: vec![25, 18, 15, 12, 10, 8, 6, 4, 2, 1],
+                season_points: KeyValueStore::new(),
+                players: Vec::new(),
+                prize_pool: Vault::new(prize_resource_address),
+                payout_table: vec![Decimal::one()],
+                final_leaderboard: Vec::new(),
+                prizes: KeyValueStore::new(),
+                ended: false,
+            }
+            .instantiate()
+            .prepare_to_globalize(owner_role)
+            .roles(roles!(
+                admin => rule!(
+                    require(
+                        admin_badge.resource_address()
+                    )
+                );
+            ))
+            .with_address(address_reservation)
+            .globalize();
+
+            (season, admin_badge)
+        }
+
+        /// Creates a competition that reports its results to the season when it is settled.
+        /// The competition is owned by holders of the season admin badge.
+        ///
+        /// # Arguments
+        ///
+        /// * `name` - The name of the competition.
+        /// * `registration_start` - The time from which users can register.
+        /// * `registration_end` - The time until which users can register.
+        /// * `competition_start` - The start time of the competition.
+        /// * `competition_end` - The end time of the competition.
+        /// * `trade_simulator_address` - The address of the TradeSimulator component.
+        /// * `fusd_reference_address` - The resource FUSD is priced by in the oracle and the
+        ///   trade simulator.
+        ///
+        /// # Returns
+        ///
+        /// The competition and a badge with the authority to manage it.
+        pub fn create_competition(
+            &mut self,
+            name: String,
+            registration_start: Instant,
+            registration_end: Instant,
+            competition_start: Instant,
+            competition_end: Instant,
+            trade_simulator_address: ComponentAddress,
+            fusd_reference_address: ResourceAddress,
+        ) -> (Global<Competition>, Bucket) {
+            assert!(!self.ended, "The season has ended.");
+
+            let (competition, admin_badge) = Competition::instantiate(
+                OwnerRole::Updatable(rule!(require(self.admin_badge_address))),
+                name.clone(),
+                registration_start,
+                registration_end,
+                competition_start,
+                competition_end,
+                trade_simulator_address,
+                fusd_reference_address,
+                self.user_token_resource_address,
+            );
+
+            let season_badge = self.competition_badge_manager.mint_non_fungible(
+                &NonFungibleLocalId::integer(self.competitions.len() as u64),
+                SeasonBadge {
+                    season_address: Runtime::global_address(),
+                    competition_name: name,
+                },
+            );
+            admin_badge.authorize_with_all(|| competition.join_season(season_badge));
+            self.competitions.push(competition);
+
+            Runtime::emit_event(SeasonCompetitionCreatedEvent {
+                competition_address: competition.address(),
+            });
+
+            (competition, admin_badge)
+        }
+
+        /// Awards season points for the final ranking of a settled competition. Called by the
+        /// competition itself.
+        ///
+        /// # Arguments
+        ///
+        /// * `season_badge_proof` - A proof of the competition's season badge.
+        /// * `ranking` - The IDs of the competition's best players, best first. Players placed
+        ///   below the points table can be left out.
+        pub fn report_results(&mut self, season_badge_proof: Proof, ranking: Vec<String>) {
+            assert!(!self.ended, "The season has ended.");
+            let competition_index = match season_badge_proof
+                .check(self.competition_badge_manager.address())
+                .as_non_fungible()
+                .non_fungible_local_id()
+            {
+                NonFungibleLocalId::Integer(id) => id.value(),
+                _ => panic!("Invalid season badge."),
+            };
+            assert!(
+                self.rankings.get(&competition_index).is_none(),
+                "The competition has already reported its results."
+            );
+
+            let mut points_awarded = Vec::new();
+            for (rank, user_id) in ranking.iter().enumerate() {
+                let Some(&points) = self.points_table.get(rank) else {
+                    break;
+                };
+                self.add_season_points(user_id, points);
+                points_awarded.push((user_id.clone(), points));
+            }
+
+            Runtime::emit_event(SeasonResultsReportedEvent {
+                competition_address: self.competitions[competition_index as usize].address(),
+                points_awarded,
+            });
+            self.rankings.insert(competition_index, ranking);
+        }
+
+        /// Adds season points to a player's total.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the player.
+        /// * `points` - The points to add.
+        fn add_season_points(&mut self, user_id: &str, points: u64) {
+            let user_id = user_id.to_string();
+            let season_points = self.season_points.get_mut(&user_id);
+            if let Some(mut season_points) = season_points {
+                *season_points += points;
+            } else {
+                drop(season_points);
+                self.season_points.insert(user_id.clone(), points);
+                self.players.push(user_id);
+            }
+        }
+
+        /// Adds tokens to the season-end prize pool.
+        ///
+        /// # Arguments
+        ///
+        /// * `tokens` - The prize tokens.
+        pub fn fund_prize_pool(&mut self, tokens: Bucket) {
+            assert!(!self.ended, "The season has ended.");
+            self.prize_pool.put(tokens);
+        }
+
+        /// Fixes the season leaderboard and assigns the prize pool. Possible once every
+        /// competition of the season has reported its results or was cancelled. Only the admin
+        /// decides when the season is over, as more competitions can be added to it until then.
+        pub fn end_season(&mut self) {
+            assert!(!self.ended, "The season has already ended.");
+            assert!(
+                !self.competitions.is_empty(),
+                "The season has no competitions."
+            );
+            for (index, competition) in self.competitions.iter().enumerate() {
+                assert!(
+                    self.rankings.get(&(index as u64)).is_some()
+                        || competition.get_status() == CompetitionStatus::Cancelled,
+                    "Not every competition of the season has finished."
+                );
+            }
+
+            let leaderboard = self.get_leaderboard();
+            let paid_ranks = self.payout_table.len().min(leaderboard.len());
+            let total_share = self.payout_table[..paid_ranks]
+                .iter()
+                .fold(Decimal::zero(), |sum, share| sum + *share);
+            let pool_amount = self.prize_pool.amount();
+            let divisibility = self
+                .prize_pool
+                .resource_manager()
+                .resource_type()
+                .divisibility()
+                .expect("Prizes must be fungible");
+
+            for (rank, standing) in leaderboard.iter().take(paid_ranks).enumerate() {
+                let amount = (pool_amount * self.payout_table[rank])
+                    .checked_div(total_share)
+                    .expect("Division by zero error")
+                    .checked_round(divisibility, RoundingMode::ToZero)
+                    .expect("Rounding error");
+                self.prizes.insert(standing.user_id.clone(), amount);
+            }
+
+            Runtime::emit_event(SeasonEndedEvent {
+                winners: leaderboard
+                    .iter()
+                    .take(paid_ranks)
+                    .map(|standing| standing.user_id.clone())
+                    .collect(),
+            });
+            self.final_leaderboard = leaderboard;
+            self.ended = true;
+        }
+
+        /// Withdraws the season prize a player has won.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_token_proof` - A proof of the user's token to verify identity.
+        ///
+        /// # Returns
+        ///
+        /// A bucket containing the prize.
+        pub fn claim_prize(&mut self, user_token_proof: Proof) -> Bucket {
+            assert!(self.ended, "The season has not ended yet.");
+            let user_id = user_token_proof
+                .check(self.user_token_resource_address)
+                .as_non_fungible()
+                .non_fungible_local_id()
+                .to_string();
+            let amount = self.prizes.remove(&user_id).expect("No prize to claim.");
+
+            Runtime::emit_event(SeasonPrizeClaimedEvent { user_id, amount });
+            self.prize_pool.take(amount)
+        }
+
+        /// Sets the season points awarded for each placement in a competition. Placements
+        /// beyond the table receive no points.
+        ///
+        /// # Arguments
+        ///
+        /// * `points_table` - The points for each placement, best first.
+        pub fn set_points_table(&mut self, points_table: Vec<u64>) {
+            assert!(!self.ended, "The season has ended.");
+            assert!(
+                points_table.windows(2).all(|pair| pair[0] >= pair[1]),
+                "A placement cannot earn more points than the one before it."
+            );
+            self.points_table = points_table;
+        }
+
+        /// Sets how the prize pool is split among the best players of the season.
+        ///
+        /// # Arguments
+        ///
+        /// * `payout_table` - The share of the prize pool for each rank, best first.
+        pub fn set_payout_table(&mut self, payout_table: Vec<Decimal>) {
+            assert!(!self.ended, "The season has ended.");
+            assert!(
+                payout_table.iter().all(|share| share.is_positive()),
+                "Every share of the payout table must be positive."
+            );
+            assert!(
+                payout_table
+                    .iter()
+                    .fold(Decimal::zero(), |sum, share| sum + *share)
+                    == Decimal::one(),
+                "The shares of the payout table must add up to 1."
+            );
+            self.payout_table = payout_table;
+        }
+
+        /// Gets the name of the season.
+        pub fn get_name(&self) -> String {
+            self.name.clone()
+        }
+
+        /// Gets the competitions of the season, in the order they were created.
+        pub fn get_competitions(&self) -> Vec<ComponentAddress> {
+            self.competitions
+                .iter()
+                .map(|competition| competition.address())
+                .collect()
+        }
+
+        /// Gets the resource address of the badges held by the season's competitions.
+        pub fn get_competition_badge_address(&self) -> ResourceAddress {
+            self.competition_badge_manager.address()
+        }
+
+        /// Gets the season points awarded for each placement, best first.
+        pub fn get_points_table(&self) -> Vec<u64> {
+            self.points_table.clone()
+        }
+
+        /// Gets the share of the prize pool for each season rank, best first.
+        pub fn get_payout_table(&self) -> Vec<Decimal> {
+            self.payout_table.clone()
+        }
+
+        /// Gets the season points of a player.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the player.
+        pub fn get_season_points(&self, user_id: String) -> u64 {
+            self.season_points
+                .get(&user_id)
+                .map(|points| *points)
+                .unwrap_or_default()
+        }
+
+        /// Gets the season leaderboard.
+        ///
+        /// # Returns
+        ///
+        /// The final leaderboard once the season has ended, otherwise the current standings.
+        /// Players with equal points keep the order in which they first scored.
+        pub fn get_leaderboard(&self) -> Vec<SeasonStanding> {
+            if self.ended {
+                return self.final_leaderboard.clone();
+            }
+
+            let mut leaderboard: Vec<SeasonStanding> = self
+                .players
+                .iter()
+                .map(|user_id| SeasonStanding {
+                    user_id: user_id.clone(),
+                    points: self.get_season_points(user_id.clone()),
+                })
+                .collect();
+            leaderboard.sort_by_key(|standing| Reverse(standing.points));
+            leaderboard
+        }
+
+        /// Gets the amount of the season-end prize pool.
+        pub fn get_prize_pool_amount(&self) -> Decimal {
+            self.prize_pool.amount()
+        }
+
+        /// Gets the unclaimed season prize of a player.
+        ///
+        /// # Arguments
+        ///
+        /// * `user_id` - The ID of the player.
+        pub fn get_prize(&self, user_id: String) -> Option<Decimal> {
+            self.prizes.get(&user_id).map(|amount| *amount)
+        }
+
+        /// Checks whether the season has ended.
+        pub fn is_ended(&self) -> bool {
+            self.ended
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use scrypto::prelude::*;
+
+/// The maximum number of resources sponsors can add to the prize pools, which bounds the cost
+/// of settling the competition.
+pub const MAX_SPONSORED_RESOURCES: usize = 5;
+
+/// How a sponsor is presented by the frontend, set by the admin.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct SponsorInfo {
+    pub name: String,
+    pub logo_url: String,
+    pub website_url: Option<String>,
+}
+
+/// A partner who funds the prize pools of a competition.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct Sponsor {
+    pub info: SponsorInfo,
+    /// The resources the admin allowed the sponsor to deposit.
+    pub prize_resources: Vec<ResourceAddress>,
+    /// The tokens deposited for the sponsor, per resource.
+    pub contributions: Vec<(ResourceAddress, Decimal)>,
+}
+
+impl Sponsor {
+    /// Creates a sponsor that has not contributed yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - How the sponsor is presented by the frontend.
+    /// * `prize_resources` - The resources the sponsor may deposit.
+    pub fn new(info: SponsorInfo, prize_resources: Vec<ResourceAddress>) -> Self {
+        assert!(!info.name.is_empty(), "The sponsor needs a name.");
+        assert!(
+            !prize_resources.is_empty() && prize_resources.len() <= MAX_SPONSORED_RESOURCES,
+            "A sponsor needs between 1 and {} prize resources.",
+            MAX_SPONSORED_RESOURCES
+        );
+        Self {
+            info,
+            prize_resources,
+            contributions: Vec::new(),
+        }
+    }
+
+    /// Asserts that the sponsor may deposit a resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_address` - The resource of the deposit.
+    pub fn assert_accepts(&self, resource_address: ResourceAddress) {
+        assert!(
+            self.prize_resources.contains(&resource_address),
+            "The sponsor cannot deposit this resource."
+        );
+    }
+
+    /// Adds a deposit to the sponsor's contribution of its resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_address` - The resource of the deposit.
+    /// * `amount` - The amount deposited.
+    pub fn add_contribution(&mut self, resource_address: ResourceAddress, amount: Decimal) {
+        match self
+            .contributions
+            .iter_mut()
+            .find(|(contribution_address, _)| *contribution_address == resource_address)
+        {
+            Some((_, contribution)) => *contribution += amount,
+            None => self.contributions.push((resource_address, amount)),
+        }
+    }
+}
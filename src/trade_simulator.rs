@@ -1,9 +1,11 @@
+use crate::events::ResourceWhitelistedEvent;
 use crate::oracle::simple_oracle::SimpleOracle;
 use scrypto::prelude::*;
 
 type LazySet<K> = KeyValueStore<K, ()>;
 
 #[blueprint]
+#[events(ResourceWhitelistedEvent)]
 mod trade_simulator {
 
     // Enable method-based authorization for roles
@@ -14,6 +16,7 @@ mod trade_simulator {
         methods {
             // Methods with public access
             trade => PUBLIC;
+            get_oracle_address => PUBLIC;
 
             // Methods with admin access
             add_new_resource => restrict_to: [simulator_manager, OWNER];
@@ -60,9 +63,23 @@ mod trade_simulator {
         /// * `address` - The resource address of the new asset to be allowed for trading.
         pub fn add_new_resource(&mut self, address: ResourceAddress) {
             self.allowed_resources.insert(address, ());
+
+            Runtime::emit_event(ResourceWhitelistedEvent {
+                resource_address: address,
+            });
+        }
+
+        /// Gets the address of the oracle used to price trades.
+        ///
+        /// # Returns
+        ///
+        /// The component address of the oracle.
+        pub fn get_oracle_address(&self) -> ComponentAddress {
+            self.oracle.address()
         }
 
         /// Facilitates a trade by burning the input tokens and minting the equivalent amount of output tokens.
+        /// Trades made here are not part of any competition and earn no achievements.
         ///
         /// # Arguments
         ///
@@ -92,7 +109,9 @@ mod trade_simulator {
             from_token.burn();
 
             // Mint new tokens and send back.
-            ResourceManager::from_address(to_token_address).mint(to_token_amount_to_mint)
+            FungibleResourceManager::from(to_token_address)
+                .mint(to_token_amount_to_mint)
+                .into()
         }
 
         /// Calculates the amount of tokens to mint based on the input token amount and token prices.
@@ -123,13 +142,11 @@ mod trade_simulator {
                 .expect("Division by zero error");
 
             // Calculate the amount of tokens to mint.
-            let to_token_amount = from_token_amount
+            from_token_amount
                 .checked_mul(price_ratio)
                 .expect("Multiplication overflow")
                 .checked_round(DIVISIBILITY_MAXIMUM, RoundingMode::ToZero)
-                .expect("Rounding error");
-
-            to_token_amount
+                .expect("Rounding error")
         }
     }
 }
@@ -1,18 +1,42 @@
 use scrypto::prelude::*;
 
+/// A single holding of a portfolio, valued in the quote resource.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PortfolioPosition {
+    pub resource_address: ResourceAddress,
+    pub amount: Decimal,
+    pub price: Decimal,
+    pub value: Decimal,
+}
+
+/// All holdings of a user asset vault, valued in the quote resource.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct Portfolio {
+    pub positions: Vec<PortfolioPosition>,
+    pub perpetual_value: Decimal, // The equity of open perpetual positions, floored at zero, set by `Competition`.
+    pub option_value: Decimal,    // The value of held options, set by `Competition`.
+    pub total_value: Decimal,
+}
+
 #[blueprint]
 mod user_asset_vault {
     struct UserAssetVault {
         assets: KeyValueStore<ResourceAddress, Vault>,
+        resource_addresses: Vec<ResourceAddress>, // The keys of `assets` in insertion order.
     }
 
     impl UserAssetVault {
         pub fn instantiate(fusd: Bucket) -> Owned<UserAssetVault> {
             // Add FUSD to the assets
+            let resource_addresses = vec![fusd.resource_address()];
             let assets = KeyValueStore::new();
             assets.insert(fusd.resource_address(), Vault::with_bucket(fusd));
 
-            Self { assets }.instantiate()
+            Self {
+                assets,
+                resource_addresses,
+            }
+            .instantiate()
         }
 
         pub fn withdraw_asset(
@@ -35,9 +59,111 @@ mod user_asset_vault {
             } else {
                 info!("Create new asset vault for {:?}", asset.resource_address());
                 drop(entry);
+                self.resource_addresses.push(asset.resource_address());
                 self.assets
                     .insert(asset.resource_address(), Vault::with_bucket(asset));
             }
         }
+
+        /// Withdraws a single non-fungible asset, e.g. an option.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The resource address of the non-fungible asset.
+        /// * `id` - The local ID of the non-fungible to withdraw.
+        ///
+        /// # Returns
+        ///
+        /// A bucket containing the non-fungible.
+        pub fn withdraw_non_fungible(
+            &mut self,
+            resource_address: ResourceAddress,
+            id: NonFungibleLocalId,
+        ) -> Bucket {
+            let vault = self
+                .assets
+                .get_mut(&resource_address)
+                .expect("Asset not found");
+            vault.as_non_fungible().take_non_fungible(&id).into()
+        }
+
+        /// Burns every asset held by the vault.
+        pub fn burn_all_assets(&mut self) {
+            for resource_address in self.resource_addresses.iter() {
+                let mut vault = self.assets.get_mut(resource_address).unwrap();
+                if vault.amount().is_positive() {
+                    vault.take_all().burn();
+                }
+            }
+        }
+
+        /// Gets the amount held of a single resource.
+        ///
+        /// # Arguments
+        ///
+        /// * `resource_address` - The resource address of the asset.
+        ///
+        /// # Returns
+        ///
+        /// The amount held, zero if the resource has never been deposited.
+        pub fn get_balance(&self, resource_address: ResourceAddress) -> Decimal {
+            self.assets
+                .get(&resource_address)
+                .map(|vault| vault.amount())
+                .unwrap_or_default()
+        }
+
+        /// Gets every resource held by the vault together with its amount.
+        ///
+        /// # Returns
+        ///
+        /// A list of resource addresses and amounts, omitting empty vaults and non-fungible
+        /// assets.
+        pub fn get_holdings(&self) -> Vec<(ResourceAddress, Decimal)> {
+            self.resource_addresses
+                .iter()
+                .filter(|resource_address| resource_address.is_fungible())
+                .map(|resource_address| (*resource_address, self.get_balance(*resource_address)))
+                .filter(|(_, amount)| amount.is_positive())
+                .collect()
+        }
+
+        /// Values every holding at the given prices.
+        ///
+        /// # Arguments
+        ///
+        /// * `prices` - The price of every held resource in the quote resource, e.g. FUSD.
+        ///
+        /// # Returns
+        ///
+        /// The valued holdings and their total value.
+        pub fn get_portfolio(&self, prices: IndexMap<ResourceAddress, Decimal>) -> Portfolio {
+            let positions: Vec<PortfolioPosition> = self
+                .get_holdings()
+                .into_iter()
+                .map(|(resource_address, amount)| {
+                    let price = *prices.get(&resource_address).expect("Price not found");
+                    let value = amount.checked_mul(price).expect("Multiplication overflow");
+
+                    PortfolioPosition {
+                        resource_address,
+                        amount,
+                        price,
+                        value,
+                    }
+                })
+                .collect();
+
+            let total_value = positions
+                .iter()
+                .fold(Decimal::zero(), |total, position| total + position.value);
+
+            Portfolio {
+                positions,
+                perpetual_value: Decimal::zero(),
+                option_value: Decimal::zero(),
+                total_value,
+            }
+        }
     }
 }
@@ -0,0 +1,75 @@
+use scrypto::prelude::*;
+
+/// How prizes unlock after the competition ends, set by the admin.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    /// The share of the prize that unlocks at the competition end, e.g. 0.2 for 20%.
+    pub cliff_share: Decimal,
+    /// The number of seconds over which the rest unlocks linearly.
+    pub duration: i64,
+}
+
+impl VestingSchedule {
+    /// Asserts that the parameters are within their valid ranges.
+    pub fn assert_valid(&self) {
+        assert!(
+            !self.cliff_share.is_negative() && self.cliff_share <= Decimal::one(),
+            "The cliff share must be between 0 and 1."
+        );
+        assert!(self.duration > 0, "The vesting duration must be positive.");
+    }
+
+    /// Calculates the part of a prize that has unlocked.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_amount` - The full prize.
+    /// * `start` - The time vesting started.
+    /// * `now` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// The cliff share plus the linearly unlocked part of the rest, nothing before the start.
+    pub fn unlocked_amount(&self, total_amount: Decimal, start: Instant, now: Instant) -> Decimal {
+        let elapsed = now.seconds_since_unix_epoch - start.seconds_since_unix_epoch;
+        if elapsed < 0 {
+            return Decimal::zero();
+        }
+        if elapsed >= self.duration {
+            return total_amount;
+        }
+
+        let linear_share = (Decimal::one() - self.cliff_share) * Decimal::from(elapsed)
+            / Decimal::from(self.duration);
+        total_amount * (self.cliff_share + linear_share)
+    }
+}
+
+/// The non-fungible data of a claim on a vesting prize. The holder withdraws the prize as it
+/// unlocks.
+#[derive(ScryptoSbor, NonFungibleData, Clone, Debug, PartialEq, Eq)]
+pub struct PrizeClaim {
+    pub resource_address: ResourceAddress,
+    pub total_amount: Decimal,
+    #[mutable]
+    pub claimed_amount: Decimal,
+    pub vesting_start: Instant,
+    pub schedule: VestingSchedule,
+}
+
+impl PrizeClaim {
+    /// Calculates the amount the holder can withdraw now.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time.
+    /// * `divisibility` - The divisibility of the prize resource.
+    pub fn claimable_amount(&self, now: Instant, divisibility: u8) -> Decimal {
+        let unlocked_amount = self
+            .schedule
+            .unlocked_amount(self.total_amount, self.vesting_start, now)
+            .checked_round(divisibility, RoundingMode::ToZero)
+            .expect("Rounding error");
+        (unlocked_amount - self.claimed_amount).max(Decimal::zero())
+    }
+}
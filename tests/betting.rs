@@ -0,0 +1,28 @@
+use scrypto_test::prelude::*;
+
+use the_trenches::betting::BettingResult;
+
+fn betting_result(winning_pool: Decimal, payout_pool: Decimal) -> BettingResult {
+    BettingResult {
+        winner_id: "#0#".to_string(),
+        winning_pool,
+        payout_pool,
+    }
+}
+
+#[test]
+fn test_payout_is_pro_rata() {
+    // 100 XRD on the winner share a pool of 400 XRD.
+    let result = betting_result(dec!(100), dec!(400));
+
+    assert_eq!(result.payout(dec!(25), dec!(25)), dec!(100));
+    assert_eq!(result.payout(dec!(75), dec!(90)), dec!(300));
+    assert_eq!(result.payout(Decimal::zero(), dec!(50)), Decimal::zero());
+}
+
+#[test]
+fn test_stakes_are_refunded_without_winning_bets() {
+    let result = betting_result(Decimal::zero(), dec!(400));
+
+    assert_eq!(result.payout(Decimal::zero(), dec!(50)), dec!(50));
+}
@@ -1,3 +1,7 @@
+mod betting;
+mod options;
+mod scoring;
 mod simulator;
 mod simulator_test_environment;
 mod unit;
+mod vesting;
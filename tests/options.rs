@@ -0,0 +1,124 @@
+use scrypto_test::prelude::*;
+
+use the_trenches::options::{
+    black_scholes_price, exp, ln, normal_cdf, OptionContract, OptionKind, SECONDS_PER_YEAR,
+};
+
+fn assert_close(actual: Decimal, expected: Decimal, tolerance: Decimal) {
+    assert!(
+        (actual - expected).checked_abs().unwrap() <= tolerance,
+        "{} is not within {} of {}",
+        actual,
+        tolerance,
+        expected
+    );
+}
+
+fn option(kind: OptionKind, strike: Decimal) -> OptionContract {
+    OptionContract {
+        underlying: XRD,
+        kind,
+        strike,
+        expiry: Instant::new(SECONDS_PER_YEAR),
+        quantity: dec!(2),
+        premium: Decimal::zero(),
+    }
+}
+
+#[test]
+fn test_ln_and_exp() {
+    assert_close(ln(Decimal::one()), Decimal::zero(), dec!("0.000000001"));
+    assert_close(ln(dec!(10)), dec!("2.302585093"), dec!("0.000000001"));
+    assert_close(ln(dec!("0.5")), dec!("-0.693147181"), dec!("0.000000001"));
+    assert_close(
+        exp(Decimal::one()),
+        dec!("2.718281828"),
+        dec!("0.000000001"),
+    );
+    assert_close(exp(dec!(-2)), dec!("0.135335283"), dec!("0.000000001"));
+}
+
+#[test]
+fn test_normal_cdf() {
+    assert_close(normal_cdf(Decimal::zero()), dec!("0.5"), dec!("0.0000001"));
+    assert_close(
+        normal_cdf(Decimal::one()),
+        dec!("0.84134475"),
+        dec!("0.0000001"),
+    );
+    assert_close(normal_cdf(dec!(-2)), dec!("0.02275013"), dec!("0.0000001"));
+}
+
+#[test]
+fn test_black_scholes_price() {
+    // A one year at-the-money option with 20% volatility.
+    let call = black_scholes_price(
+        OptionKind::Call,
+        dec!(100),
+        dec!(100),
+        dec!("0.2"),
+        Decimal::one(),
+    );
+    let put = black_scholes_price(
+        OptionKind::Put,
+        dec!(100),
+        dec!(100),
+        dec!("0.2"),
+        Decimal::one(),
+    );
+
+    assert_close(call, dec!("7.965567"), dec!("0.0001"));
+    assert_close(put, call, dec!("0.00001"));
+}
+
+#[test]
+fn test_black_scholes_put_call_parity() {
+    // Without interest, a call minus a put is worth the spot minus the strike.
+    let call = black_scholes_price(
+        OptionKind::Call,
+        dec!(120),
+        dec!(100),
+        dec!("0.8"),
+        dec!("0.25"),
+    );
+    let put = black_scholes_price(
+        OptionKind::Put,
+        dec!(120),
+        dec!(100),
+        dec!("0.8"),
+        dec!("0.25"),
+    );
+
+    assert_close(call - put, dec!(20), dec!("0.00001"));
+}
+
+#[test]
+fn test_option_value_at_expiry_is_the_payoff() {
+    let call = option(OptionKind::Call, dec!(100));
+    let put = option(OptionKind::Put, dec!(100));
+    let expiry = Instant::new(SECONDS_PER_YEAR);
+
+    assert_eq!(call.value(dec!(130), dec!("0.5"), expiry), dec!(60));
+    assert_eq!(put.value(dec!(130), dec!("0.5"), expiry), Decimal::zero());
+    assert_eq!(put.payoff(dec!(90)), dec!(20));
+}
+
+#[test]
+fn test_expired_option_keeps_its_payoff() {
+    let call = option(OptionKind::Call, dec!(100));
+    let long_after_expiry = Instant::new(2 * SECONDS_PER_YEAR);
+
+    assert_eq!(
+        call.value(dec!(130), dec!("0.5"), long_after_expiry),
+        dec!(60)
+    );
+}
+
+#[test]
+fn test_option_value_before_expiry() {
+    let call = option(OptionKind::Call, dec!(100));
+
+    let value = call.value(dec!(100), dec!("0.2"), Instant::new(0));
+
+    assert_close(value, dec!("15.931135"), dec!("0.0002"));
+}
@@ -0,0 +1,74 @@
+use scrypto_test::prelude::*;
+
+use the_trenches::competition::EquityPoint;
+use the_trenches::scoring::{max_drawdown, roi, sharpe_ratio, ScoringRule};
+
+fn equity_curve(values: &[Decimal]) -> Vec<EquityPoint> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(hour, value)| EquityPoint {
+            timestamp: Instant::new(0).add_hours(hour as i64).unwrap(),
+            value: *value,
+        })
+        .collect()
+}
+
+#[test]
+fn test_roi() {
+    assert_eq!(roi(dec!(10000), dec!(15000)), dec!("0.5"));
+    assert_eq!(roi(dec!(10000), dec!(8000)), dec!("-0.2"));
+    assert_eq!(roi(Decimal::zero(), dec!(8000)), Decimal::zero());
+}
+
+#[test]
+fn test_max_drawdown() {
+    let values = [dec!(100), dec!(120), dec!(90), dec!(130), dec!(117)];
+
+    assert_eq!(max_drawdown(&values), dec!("0.25"));
+    assert_eq!(max_drawdown(&[dec!(100), dec!(110)]), Decimal::zero());
+}
+
+#[test]
+fn test_sharpe_ratio() {
+    // Returns of 10% and 30%: a mean of 20% and a standard deviation of 10%.
+    let values = [dec!(100), dec!(110), dec!(143)];
+
+    assert_eq!(sharpe_ratio(&values), dec!(2));
+    assert_eq!(sharpe_ratio(&[dec!(100), dec!(110)]), Decimal::zero());
+    assert_eq!(
+        sharpe_ratio(&[dec!(100), dec!(110), dec!(121)]),
+        Decimal::zero()
+    );
+}
+
+#[test]
+fn test_higher_score_is_better_for_every_scoring_rule() {
+    let steady = equity_curve(&[dec!(10500), dec!(11000)]);
+    let volatile = equity_curve(&[dec!(14000), dec!(7000)]);
+
+    let score = |rule: ScoringRule, curve: &[EquityPoint], value: Decimal| {
+        rule.score(dec!(10000), curve, value)
+    };
+
+    assert!(
+        score(ScoringRule::HighestRoi, &volatile, dec!(12000))
+            > score(ScoringRule::HighestRoi, &steady, dec!(11500))
+    );
+    assert!(
+        score(ScoringRule::LowestRoi, &steady, dec!(11500))
+            > score(ScoringRule::LowestRoi, &volatile, dec!(12000))
+    );
+    assert!(
+        score(ScoringRule::SharpeRatio, &steady, dec!(11500))
+            > score(ScoringRule::SharpeRatio, &volatile, dec!(12000))
+    );
+    assert!(
+        score(ScoringRule::LowestMaxDrawdown, &steady, dec!(11500))
+            > score(ScoringRule::LowestMaxDrawdown, &volatile, dec!(12000))
+    );
+    assert_eq!(
+        score(ScoringRule::AbsolutePnl, &steady, dec!(11500)),
+        dec!(1500)
+    );
+}
@@ -1,26 +1,57 @@
 use scrypto_test::prelude::*;
 
 use crate::simulator_test_environment::SimulatorTestEnvironment;
+use the_trenches::events::{
+    PriceUpdatedEvent, ResourceWhitelistedEvent, TradeExecutedEvent, UserRegisteredEvent,
+};
 
 #[test]
 fn test_env_setup() {
-    let mut env = SimulatorTestEnvironment::new();
-    env.ledger_simulator;
+    SimulatorTestEnvironment::new();
 }
 
 #[test]
-fn test_instatiate_competition_and_register() {
-    // Setup the environment
-    let mut ledger = LedgerSimulatorBuilder::new().build();
+fn test_oracle_and_simulator_updates_emit_events() {
+    let mut env = SimulatorTestEnvironment::new();
+    let bitcoin = env
+        .ledger_simulator
+        .create_freely_mintable_fungible_resource(OwnerRole::None, None, 18, env.account);
+    let (simple_oracle, trade_simulator) = (env.simple_oracle, env.trade_simulator);
+
+    let receipt = env.execute_as_protocol_manager(|builder| {
+        builder
+            .call_method(
+                simple_oracle,
+                "set_price",
+                manifest_args!(bitcoin, dec!(96913)),
+            )
+            .call_method(trade_simulator, "add_new_resource", manifest_args!(bitcoin))
+    });
+    let commit_result = receipt.expect_commit_success();
 
-    // Create an account
-    let (public_key, _private_key, _account) = ledger.new_allocated_account();
+    let price_events = env
+        .ledger_simulator
+        .extract_events_of_type::<PriceUpdatedEvent>(commit_result);
+    let whitelist_events = env
+        .ledger_simulator
+        .extract_events_of_type::<ResourceWhitelistedEvent>(commit_result);
 
-    // Publish package
-    let package_address = ledger.compile_and_publish(this_package!());
+    assert_eq!(price_events.len(), 1);
+    assert_eq!(price_events[0].resource_address, bitcoin);
+    assert_eq!(price_events[0].price, dec!(96913));
+    assert_eq!(whitelist_events.len(), 1);
+    assert_eq!(whitelist_events[0].resource_address, bitcoin);
+}
+
+#[test]
+fn test_instantiate_competition_and_register() {
+    let mut env = SimulatorTestEnvironment::new();
+    let (account, user_token) = (env.account, env.user_token);
 
     // Test the `instantiate` function of a competition.
-    let registration_start = ledger.get_current_time(TimePrecisionV2::Minute);
+    let registration_start = env
+        .ledger_simulator
+        .get_current_time(TimePrecisionV2::Minute);
     let registration_end = registration_start.add_days(5).unwrap();
     let competition_start = registration_end.add_days(1).unwrap();
     let competition_end = competition_start.add_days(7).unwrap();
@@ -28,66 +59,329 @@ fn test_instatiate_competition_and_register() {
     let manifest = ManifestBuilder::new()
         .lock_fee_from_faucet()
         .call_function(
-            package_address,
+            env.package_address,
             "Competition",
             "instantiate",
-            manifest_args!(
+            (
+                OwnerRole::None,
+                "Season 3",
                 registration_start,
                 registration_end,
                 competition_start,
-                competition_end
+                competition_end,
+                env.trade_simulator,
+                env.fusd,
+                user_token,
             ),
         )
+        .try_deposit_entire_worktop_or_abort(account, None)
         .build();
-    let receipt = ledger.execute_manifest(
+    let competition = env
+        .ledger_simulator
+        .execute_manifest(manifest, vec![])
+        .expect_commit_success()
+        .new_component_addresses()[0];
+
+    // Test the `register` method.
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(
+            account,
+            user_token,
+            [NonFungibleLocalId::integer(1)],
+        )
+        .pop_from_auth_zone("user_token_proof")
+        .call_method_with_name_lookup(competition, "register", |lookup| {
+            (
+                lookup.proof("user_token_proof"),
+                None::<ManifestBucket>,
+                None::<String>,
+            )
+        })
+        .build();
+    let receipt = env.ledger_simulator.execute_manifest(
         manifest,
-        vec![NonFungibleGlobalId::from_public_key(&public_key)],
+        vec![NonFungibleGlobalId::from_public_key(&env.public_key)],
     );
-    println!("{:?}\n", receipt);
-    let competition_component = receipt.expect_commit(true).new_component_addresses()[0];
+    let commit_result = receipt.expect_commit_success();
+
+    let registered_events = env
+        .ledger_simulator
+        .extract_events_of_type::<UserRegisteredEvent>(commit_result);
+    assert_eq!(registered_events.len(), 1);
+    assert_eq!(registered_events[0].user_id, "#1#");
+    assert_eq!(registered_events[0].starting_balance, dec!(10000));
+}
+
+#[test]
+fn test_competition_creates_its_own_virtual_assets() {
+    let mut env = SimulatorTestEnvironment::new();
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(env.competition, "get_fusd_address", manifest_args!())
+        .call_method(env.competition, "get_virtual_assets", manifest_args!())
+        .build();
+    let receipt = env.ledger_simulator.execute_manifest(manifest, vec![]);
+    let commit_result = receipt.expect_commit_success();
+    let fusd: ResourceAddress = commit_result.output(1);
+    let virtual_assets: Vec<(ResourceAddress, ResourceAddress)> = commit_result.output(2);
+
+    assert_ne!(fusd, env.fusd);
+    assert_eq!(virtual_assets, vec![(fusd, env.fusd)]);
+    assert_eq!(
+        env.ledger_simulator.get_metadata(fusd.into(), "name"),
+        Some(MetadataValue::String("cFUSD – Season 3".to_string()))
+    );
+
+    // Only the competition can mint its virtual assets.
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .mint_fungible(fusd, dec!(1000))
+        .try_deposit_entire_worktop_or_abort(env.account, None)
+        .build();
+    env.ledger_simulator
+        .execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(&env.public_key)],
+        )
+        .expect_commit_failure();
+}
+
+#[test]
+fn test_registered_user_can_trade_restricted_virtual_assets() {
+    let mut env = SimulatorTestEnvironment::new();
+    let (account, user_token, fusd) = (env.account, env.user_token, env.fusd);
+    let (simple_oracle, trade_simulator) = (env.simple_oracle, env.trade_simulator);
+    let bitcoin =
+        env.ledger_simulator
+            .create_fungible_resource(dec!(0), DIVISIBILITY_MAXIMUM, account);
+
+    env.execute_as_protocol_manager(|builder| {
+        builder
+            .call_method(simple_oracle, "set_price", manifest_args!(fusd, dec!(1)))
+            .call_method(
+                simple_oracle,
+                "set_price",
+                manifest_args!(bitcoin, dec!(100)),
+            )
+            .call_method(trade_simulator, "add_new_resource", manifest_args!(fusd))
+            .call_method(trade_simulator, "add_new_resource", manifest_args!(bitcoin))
+    })
+    .expect_commit_success();
+
+    // Instantiate a competition that is open for registration
+    let registration_start = env
+        .ledger_simulator
+        .get_current_time(TimePrecisionV2::Minute);
+    let competition_start = registration_start.add_days(1).unwrap();
+    let competition_end = competition_start.add_days(7).unwrap();
 
-    // Test the `instantiate` function of a trade vault.
     let manifest = ManifestBuilder::new()
         .lock_fee_from_faucet()
         .call_function(
-            package_address,
-            "TradeVault",
+            env.package_address,
+            "Competition",
             "instantiate",
-            manifest_args!(),
+            (
+                OwnerRole::None,
+                "Season 3",
+                registration_start,
+                competition_start,
+                competition_start,
+                competition_end,
+                trade_simulator,
+                fusd,
+                user_token,
+            ),
         )
+        .try_deposit_entire_worktop_or_abort(account, None)
         .build();
-    let receipt = ledger.execute_manifest(
-        manifest,
-        vec![NonFungibleGlobalId::from_public_key(&public_key)],
-    );
-    println!("{:?}\n", receipt);
-    let trade_vault_component = receipt.expect_commit(true).new_component_addresses()[0];
+    let commit_result = env
+        .ledger_simulator
+        .execute_manifest(manifest, vec![])
+        .expect_commit_success()
+        .clone();
+    let competition = commit_result.new_component_addresses()[0];
+    let admin_badge = commit_result.new_resource_addresses()[0];
 
-    // Test vault registration
+    // Add a virtual bitcoin and register
     let manifest = ManifestBuilder::new()
         .lock_fee_from_faucet()
+        .create_proof_from_account_of_amount(account, admin_badge, dec!(1))
         .call_method(
-            competition_component,
-            "register",
-            manifest_args!(trade_vault_component),
+            competition,
+            "add_virtual_asset",
+            manifest_args!(bitcoin, "BTC"),
+        )
+        .call_method(competition, "get_fusd_address", manifest_args!())
+        .create_proof_from_account_of_non_fungibles(
+            account,
+            user_token,
+            [NonFungibleLocalId::integer(1)],
         )
+        .pop_from_auth_zone("user_token_proof")
+        .call_method_with_name_lookup(competition, "register", |lookup| {
+            (
+                lookup.proof("user_token_proof"),
+                None::<ManifestBucket>,
+                None::<String>,
+            )
+        })
         .build();
-    let receipt = ledger.execute_manifest(
+    let commit_result = env
+        .ledger_simulator
+        .execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(&env.public_key)],
+        )
+        .expect_commit_success()
+        .clone();
+    let virtual_bitcoin: ResourceAddress = commit_result.output(2);
+    let virtual_fusd: ResourceAddress = commit_result.output(3);
+
+    // Trade once the competition has started
+    let round = env
+        .ledger_simulator
+        .get_consensus_manager_state()
+        .round
+        .number()
+        + 1;
+    env.ledger_simulator.advance_to_round_at_timestamp(
+        Round::of(round),
+        competition_start.seconds_since_unix_epoch * 1000,
+    );
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_non_fungibles(
+            account,
+            user_token,
+            [NonFungibleLocalId::integer(1)],
+        )
+        .pop_from_auth_zone("user_token_proof")
+        .call_method_with_name_lookup(competition, "trade", |lookup| {
+            (
+                lookup.proof("user_token_proof"),
+                virtual_fusd,
+                virtual_bitcoin,
+                dec!(1000),
+            )
+        })
+        .build();
+    let receipt = env.ledger_simulator.execute_manifest(
         manifest,
-        vec![NonFungibleGlobalId::from_public_key(&public_key)],
+        vec![NonFungibleGlobalId::from_public_key(&env.public_key)],
     );
-    println!("{:?}\n", receipt);
-    receipt.expect_commit_success();
+    let commit_result = receipt.expect_commit_success();
+
+    let trade_events = env
+        .ledger_simulator
+        .extract_events_of_type::<TradeExecutedEvent>(commit_result);
+    assert_eq!(trade_events.len(), 1);
+    assert_eq!(trade_events[0].trade.to_address, virtual_bitcoin);
+    assert_eq!(trade_events[0].trade.to_amount, dec!(10));
+    assert_eq!(
+        env.ledger_simulator
+            .get_component_balance(account, virtual_bitcoin),
+        Decimal::zero()
+    );
+}
+
+#[test]
+fn test_virtual_assets_cannot_be_withdrawn_from_user_asset_vault() {
+    let mut env = SimulatorTestEnvironment::new();
+    let (account, user_token) = (env.account, env.user_token);
+
+    // Instantiate a competition that is open for registration
+    let registration_start = env
+        .ledger_simulator
+        .get_current_time(TimePrecisionV2::Minute);
+    let competition_start = registration_start.add_days(1).unwrap();
+    let competition_end = competition_start.add_days(7).unwrap();
 
-    // Test the `trade` method.
     let manifest = ManifestBuilder::new()
         .lock_fee_from_faucet()
-        .call_method(trade_vault_component, "trade", manifest_args!())
+        .call_function(
+            env.package_address,
+            "Competition",
+            "instantiate",
+            (
+                OwnerRole::None,
+                "Season 3",
+                registration_start,
+                competition_start,
+                competition_start,
+                competition_end,
+                env.trade_simulator,
+                env.fusd,
+                user_token,
+            ),
+        )
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let competition = env
+        .ledger_simulator
+        .execute_manifest(manifest, vec![])
+        .expect_commit_success()
+        .new_component_addresses()[0];
+
+    // Register, which puts the starting balance into a new user asset vault
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(competition, "get_fusd_address", manifest_args!())
+        .create_proof_from_account_of_non_fungibles(
+            account,
+            user_token,
+            [NonFungibleLocalId::integer(1)],
+        )
+        .pop_from_auth_zone("user_token_proof")
+        .call_method_with_name_lookup(competition, "register", |lookup| {
+            (
+                lookup.proof("user_token_proof"),
+                None::<ManifestBucket>,
+                None::<String>,
+            )
+        })
         .build();
-    let receipt = ledger.execute_manifest(
+    let commit_result = env
+        .ledger_simulator
+        .execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(&env.public_key)],
+        )
+        .expect_commit_success()
+        .clone();
+    let virtual_fusd: ResourceAddress = commit_result.output(1);
+
+    let vaults = env
+        .ledger_simulator
+        .get_component_vaults(competition, virtual_fusd);
+    assert_eq!(vaults.len(), 1);
+    assert_eq!(
+        env.ledger_simulator.inspect_vault_balance(vaults[0]),
+        Some(dec!(10000))
+    );
+
+    // The user cannot move the virtual FUSD out of the vault into their account
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .recall(InternalAddress::new_or_panic(vaults[0].0), dec!(1000))
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let receipt = env.ledger_simulator.execute_manifest(
         manifest,
-        vec![NonFungibleGlobalId::from_public_key(&public_key)],
+        vec![NonFungibleGlobalId::from_public_key(&env.public_key)],
+    );
+
+    receipt.expect_specific_failure(is_auth_error);
+    assert_eq!(
+        env.ledger_simulator
+            .get_component_balance(account, virtual_fusd),
+        Decimal::zero()
+    );
+    assert_eq!(
+        env.ledger_simulator.inspect_vault_balance(vaults[0]),
+        Some(dec!(10000))
     );
-    println!("{:?}\n", receipt);
-    receipt.expect_commit_success();
 }
@@ -2,6 +2,14 @@ use scrypto_test::prelude::*;
 
 pub struct SimulatorTestEnvironment {
     pub ledger_simulator: DefaultLedgerSimulator,
+    pub public_key: Secp256k1PublicKey,
+    pub account: ComponentAddress,
+    pub protocol_manager_badge: ResourceAddress,
+    pub package_address: PackageAddress,
+    pub fusd: ResourceAddress,
+    pub user_token: ResourceAddress,
+    pub simple_oracle: ComponentAddress,
+    pub trade_simulator: ComponentAddress,
     pub competition: ComponentAddress,
 }
 
@@ -10,10 +18,10 @@ impl SimulatorTestEnvironment {
         let mut ledger_simulator = LedgerSimulatorBuilder::new().without_kernel_trace().build();
 
         // The account that everything gets deposited into throughout the tests.
-        let (public_key, private_key, account) = ledger_simulator.new_account(false);
+        let (public_key, _private_key, account) = ledger_simulator.new_account(false);
 
         let protocol_manager_badge = ledger_simulator.create_fungible_resource(dec!(1), 0, account);
-        let protocol_owner_badge = ledger_simulator.create_fungible_resource(dec!(1), 0, account);
+        let _protocol_owner_badge = ledger_simulator.create_fungible_resource(dec!(1), 0, account);
 
         let protocol_manager_rule = rule!(require(protocol_manager_badge));
 
@@ -28,9 +36,8 @@ impl SimulatorTestEnvironment {
             account,
         );
 
-        // Create FUSD resource
-        let user_token =
-            ledger_simulator.create_everything_allowed_non_fungible_resource(OwnerRole::None);
+        // Create user token resource, minting the user tokens #1#, #2# and #3# into the account
+        let user_token = ledger_simulator.create_non_fungible_resource(account);
 
         // Initialize a simple oracle
         let simple_oracle = ledger_simulator
@@ -89,6 +96,9 @@ impl SimulatorTestEnvironment {
                 "instantiate",
                 (
                     OwnerRole::None,
+                    "Season 3",
+                    i64::from(0),
+                    i64::from(1),
                     i64::from(0),
                     i64::from(1),
                     trade_simulator,
@@ -118,7 +128,40 @@ impl SimulatorTestEnvironment {
 
         Self {
             ledger_simulator,
+            public_key,
+            account,
+            protocol_manager_badge,
+            package_address,
+            fusd,
+            user_token,
+            simple_oracle,
+            trade_simulator,
             competition,
         }
     }
 }
+
+impl SimulatorTestEnvironment {
+    /// Executes a manifest signed by the test account, with a proof of the protocol manager
+    /// badge in the auth zone.
+    pub fn execute_as_protocol_manager(
+        &mut self,
+        build: impl FnOnce(ManifestBuilder) -> ManifestBuilder,
+    ) -> TransactionReceipt {
+        let manifest = build(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .create_proof_from_account_of_amount(
+                    self.account,
+                    self.protocol_manager_badge,
+                    dec!(1),
+                ),
+        )
+        .build();
+
+        self.ledger_simulator.execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(&self.public_key)],
+        )
+    }
+}
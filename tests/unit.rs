@@ -1,8 +1,22 @@
 use scrypto_test::prelude::*;
 
+use the_trenches::achievement::Achievement;
 use the_trenches::competition::competition_test::*;
+use the_trenches::competition::{CompetitionStatus, LateRegistrationPolicy, RegistrationAccess};
+use the_trenches::copy_trading::CopySettings;
+use the_trenches::keeper::KeeperRewardConfig;
+use the_trenches::options::OptionKind;
 use the_trenches::oracle::simple_oracle_test::*;
+use the_trenches::perpetuals::{PerpetualMarketConfig, PositionSide, FUNDING_INTERVAL};
+use the_trenches::referral::ReferralConfig;
+use the_trenches::risk::{trade_commitment, RiskRules, TradeProtection, REVEAL_WINDOW};
+use the_trenches::scheduled_orders::MAX_OPEN_ORDERS;
+use the_trenches::scoring::ScoringRule;
+use the_trenches::season::season_test::*;
+use the_trenches::season::SeasonBadge;
+use the_trenches::sponsorship::SponsorInfo;
 use the_trenches::trade_simulator::trade_simulator_test::*;
+use the_trenches::vesting::VestingSchedule;
 
 #[derive(ScryptoSbor, scrypto::NonFungibleData)]
 struct User {
@@ -24,9 +38,17 @@ impl ResourceAddresses {
 
 struct UnitTestEnvironment {
     env: TestEnvironment<InMemorySubstateDatabase>,
+    package_address: PackageAddress,
+    oracle: SimpleOracle,
+    trade_simulator: TradeSimulator,
     competition: Competition,
-    resource_addresses: ResourceAddresses,
+    reference_addresses: ResourceAddresses, // The assets priced by the oracle.
+    resource_addresses: ResourceAddresses,  // The competition's virtual assets.
+    user_token_address: ResourceAddress,
     user_token_proof: Proof,
+    user_id: String,
+    rival_token_proof: Proof,
+    rival_id: String,
 }
 
 impl UnitTestEnvironment {
@@ -49,20 +71,36 @@ impl UnitTestEnvironment {
             .resource_address(&mut env)
             .map(|address| rule!(require(address)))?;
 
-        // Create a user token and proof
+        // Create two user tokens and proofs
         let user_token = ResourceBuilder::new_ruid_non_fungible(OwnerRole::None)
             .mint_initial_supply(
-                [(User {
-                    name: "Trader".into(),
-                })],
+                [
+                    User {
+                        name: "Trader".into(),
+                    },
+                    User {
+                        name: "Rival".into(),
+                    },
+                ],
                 &mut env,
             )
             .unwrap();
 
-        let user_token_proof = user_token.create_proof_of_all(&mut env).unwrap();
+        let user_ids: Vec<NonFungibleLocalId> = user_token
+            .non_fungible_local_ids(&mut env)?
+            .into_iter()
+            .collect();
+        let rival_token =
+            user_token.take_non_fungibles(indexset!(user_ids[1].clone()), &mut env)?;
 
-        // Create resources
-        let resource_addresses = ResourceAddresses {
+        let user_token_address = user_token.resource_address(&mut env)?;
+        let user_token_proof = user_token.create_proof_of_all(&mut env)?.into();
+        let user_id = user_ids[0].to_string();
+        let rival_token_proof = rival_token.create_proof_of_all(&mut env)?.into();
+        let rival_id = user_ids[1].to_string();
+
+        // Create reference resources
+        let reference_addresses = ResourceAddresses {
             bitcoin: Self::create_resource(&mut env),
             ethereum: Self::create_resource(&mut env),
             solana: Self::create_resource(&mut env),
@@ -80,7 +118,7 @@ impl UnitTestEnvironment {
         )?;
 
         // Submitting some dummy prices to the oracle
-        for &resource_address in resource_addresses.as_vec() {
+        for &resource_address in reference_addresses.as_vec() {
             oracle.set_price(resource_address, dec!(1), &mut env)?;
         }
 
@@ -94,44 +132,76 @@ impl UnitTestEnvironment {
         )?;
 
         // Add resources to whitelist
-        for &resource_address in resource_addresses.as_vec() {
+        for &resource_address in reference_addresses.as_vec() {
             trade_simulator.add_new_resource(resource_address, &mut env)?;
         }
 
         // Init a competition
-        let competition_start = env.get_current_time().add_days(1).unwrap();
+        let registration_start = env.get_current_time();
+        let competition_start = registration_start.add_days(1).unwrap();
         let competition_end = competition_start.add_days(7).unwrap();
 
-        let (competition, _) = Competition::instantiate(
+        let (mut competition, _) = Competition::instantiate(
             OwnerRole::None,
+            "Season 3".to_string(),
+            registration_start,
+            competition_start,
             competition_start,
             competition_end,
             trade_simulator.try_into().unwrap(),
-            resource_addresses.fusd,
-            user_token.resource_address(&mut env).unwrap(),
+            reference_addresses.fusd,
+            user_token_address,
             package_address,
             &mut env,
         )?;
 
+        // Create the competition's virtual assets
+        let resource_addresses = ResourceAddresses {
+            bitcoin: competition.add_virtual_asset(
+                reference_addresses.bitcoin,
+                "BTC".to_string(),
+                &mut env,
+            )?,
+            ethereum: competition.add_virtual_asset(
+                reference_addresses.ethereum,
+                "ETH".to_string(),
+                &mut env,
+            )?,
+            solana: competition.add_virtual_asset(
+                reference_addresses.solana,
+                "SOL".to_string(),
+                &mut env,
+            )?,
+            fusd: competition.get_fusd_address(&mut env)?,
+        };
+
         Ok(Self {
             env,
+            package_address,
+            oracle,
+            trade_simulator,
             competition,
+            reference_addresses,
             resource_addresses,
+            user_token_address,
             user_token_proof,
+            user_id,
+            rival_token_proof,
+            rival_id,
         })
     }
 
+    fn create_entry_fee_tokens(
+        env: &mut TestEnvironment<InMemorySubstateDatabase>,
+    ) -> Result<FungibleBucket, RuntimeError> {
+        ResourceBuilder::new_fungible(OwnerRole::None)
+            .divisibility(DIVISIBILITY_MAXIMUM)
+            .mint_initial_supply(1000, env)
+    }
+
     fn create_resource(env: &mut TestEnvironment<InMemorySubstateDatabase>) -> ResourceAddress {
-        ResourceBuilder::new_fungible(OwnerRole::Fixed(rule!(allow_all)))
+        ResourceBuilder::new_fungible(OwnerRole::None)
             .divisibility(DIVISIBILITY_MAXIMUM)
-            .mint_roles(mint_roles! {
-                minter => rule!(allow_all);
-                minter_updater => rule!(allow_all);
-            })
-            .burn_roles(burn_roles! {
-                burner => rule!(allow_all);
-                burner_updater => rule!(allow_all);
-            })
             .mint_initial_supply(dec!(0), env)
             .and_then(|bucket| bucket.resource_address(env))
             .unwrap()
@@ -149,7 +219,7 @@ fn test_vault_can_register() -> Result<(), RuntimeError> {
     } = UnitTestEnvironment::new()?;
 
     // Act
-    let result = competition.register(user_token_proof, env);
+    let result = competition.register(user_token_proof, None, None, env);
 
     // Assert
     assert!(result.is_ok());
@@ -169,7 +239,347 @@ fn test_vault_cannot_register_after_competition_started() -> Result<(), RuntimeE
     env.set_current_time(Instant::new(0).add_days(2).unwrap());
 
     // Act
-    let result = competition.register(user_token_proof, env);
+    let result = competition.register(user_token_proof, None, None, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_vault_cannot_register_before_registration_opens() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let registration_start_time = competition.get_registration_start_time(env)?;
+    competition.set_registration_start_time(registration_start_time.add_hours(1).unwrap(), env)?;
+
+    // Act
+    let result = competition.register(user_token_proof, None, None, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_late_registration_receives_pro_rated_balance() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    competition.set_registration_end_time(competition_end_time, env)?;
+    competition.set_late_registration_policy(LateRegistrationPolicy::ProRated, env)?;
+
+    // Act
+    env.set_current_time(competition_start_time.add_hours(42).unwrap());
+    competition.register(user_token_proof, None, None, env)?;
+
+    // Assert
+    let portfolio = competition.get_user_portfolio(user_id, env)?;
+    assert_eq!(
+        portfolio.positions[0].resource_address,
+        resource_addresses.fusd
+    );
+    assert_eq!(portfolio.positions[0].amount, dec!(7500));
+    Ok(())
+}
+
+#[test]
+fn test_late_registration_receives_penalized_balance() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    competition.set_registration_end_time(competition_start_time.add_days(1).unwrap(), env)?;
+    competition
+        .set_late_registration_policy(LateRegistrationPolicy::Penalized(dec!("0.2")), env)?;
+
+    // Act
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    competition.register(user_token_proof, None, None, env)?;
+
+    // Assert
+    let portfolio = competition.get_user_portfolio(user_id, env)?;
+    assert_eq!(portfolio.total_value, dec!(8000));
+    Ok(())
+}
+
+#[test]
+fn test_late_registrant_is_scored_against_own_starting_balance() -> Result<(), RuntimeError> {
+    for scoring_rule in [
+        ScoringRule::HighestRoi,
+        ScoringRule::LowestRoi,
+        ScoringRule::SharpeRatio,
+        ScoringRule::LowestMaxDrawdown,
+        ScoringRule::AbsolutePnl,
+    ] {
+        // Arrange
+        let UnitTestEnvironment {
+            ref mut env,
+            mut oracle,
+            reference_addresses,
+            mut competition,
+            resource_addresses,
+            user_token_proof,
+            user_id,
+            rival_token_proof,
+            rival_id,
+            ..
+        } = UnitTestEnvironment::new()?;
+
+        let competition_start_time = competition.get_competition_start_time(env)?;
+        competition.set_scoring_rule(scoring_rule, env)?;
+        competition.set_registration_end_time(competition_start_time.add_days(1).unwrap(), env)?;
+        competition
+            .set_late_registration_policy(LateRegistrationPolicy::Penalized(dec!("0.2")), env)?;
+        competition.register(user_token_proof, None, None, env)?;
+
+        env.set_current_time(competition_start_time.add_hours(1).unwrap());
+        competition.register(rival_token_proof.clone(env)?, None, None, env)?;
+        competition.take_snapshot(100, None, env)?;
+        competition.trade(
+            rival_token_proof,
+            resource_addresses.fusd,
+            resource_addresses.bitcoin,
+            dec!(4000),
+            env,
+        )?;
+        oracle.set_price(reference_addresses.bitcoin, dec!("1.5"), env)?;
+
+        // Act
+        let leaderboard = competition.get_leaderboard(env)?;
+
+        // Assert
+        let rival = leaderboard
+            .iter()
+            .find(|entry| entry.user_id == rival_id)
+            .unwrap();
+        assert_eq!(rival.portfolio_value, dec!(10000));
+        match scoring_rule {
+            ScoringRule::HighestRoi => {
+                assert_eq!(leaderboard[0].user_id, rival_id);
+                assert_eq!(rival.score, dec!("0.25"));
+            }
+            ScoringRule::LowestRoi => {
+                assert_eq!(leaderboard[0].user_id, user_id);
+                assert_eq!(rival.score, dec!("-0.25"));
+            }
+            ScoringRule::SharpeRatio => {
+                assert_eq!(leaderboard[0].user_id, rival_id);
+                assert!(rival.score.is_positive());
+            }
+            ScoringRule::LowestMaxDrawdown => {
+                assert_eq!(leaderboard[0].user_id, user_id);
+                assert_eq!(rival.score, Decimal::zero());
+            }
+            ScoringRule::AbsolutePnl => {
+                assert_eq!(leaderboard[0].user_id, rival_id);
+                assert_eq!(rival.score, dec!(2000));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_cannot_register_when_competition_is_full() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_max_participants(Some(1), env)?;
+    competition.register(user_token_proof, None, None, env)?;
+    let max_participants = competition.get_max_participants(env)?;
+
+    // Act
+    let result = competition.register(rival_token_proof, None, None, env);
+
+    // Assert
+    assert_eq!(max_participants, Some(1));
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_allowlisted_competition_only_accepts_allowlisted_users() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_registration_access(RegistrationAccess::Allowlist, env)?;
+    competition.add_to_allowlist(vec![user_id.clone()], env)?;
+
+    // Act
+    competition.register(user_token_proof, None, None, env)?;
+    let participants = competition.get_participants(env)?;
+    let rival_result = competition.register(rival_token_proof, None, None, env);
+
+    // Assert
+    assert_eq!(participants, vec![user_id]);
+    assert!(rival_result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_badge_gated_competition_requires_badge() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let community_badge = ResourceBuilder::new_fungible(OwnerRole::None)
+        .divisibility(0)
+        .mint_initial_supply(1, env)?;
+    let community_badge_address = community_badge.resource_address(env)?;
+    competition.set_registration_access(RegistrationAccess::Badge(community_badge_address), env)?;
+
+    // Act
+    competition.register_with_badge(
+        user_token_proof,
+        community_badge.create_proof_of_all(env)?.into(),
+        None,
+        None,
+        env,
+    )?;
+    let participants = competition.get_participants(env)?;
+    let rival_result = competition.register(rival_token_proof, None, None, env);
+
+    // Assert
+    assert_eq!(participants, vec![user_id]);
+    assert!(rival_result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_invite_code_can_only_be_used_once() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_registration_access(RegistrationAccess::InviteCode, env)?;
+    competition.add_invite_codes(vec![hash("APE-2024".as_bytes())], env)?;
+
+    // Act
+    competition.register_with_invite_code(
+        user_token_proof,
+        "APE-2024".to_string(),
+        None,
+        None,
+        env,
+    )?;
+    let participants = competition.get_participants(env)?;
+    let rival_result = competition.register_with_invite_code(
+        rival_token_proof,
+        "APE-2024".to_string(),
+        None,
+        None,
+        env,
+    );
+
+    // Assert
+    assert_eq!(participants, vec![user_id]);
+    assert!(rival_result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_user_can_unregister_before_competition_starts() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let entry_fee_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let entry_fee_address = entry_fee_tokens.resource_address(env)?;
+    competition.set_entry_fee(entry_fee_address, dec!(100), env)?;
+    competition.register(
+        user_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+
+    // Act
+    let refund = competition.unregister(user_token_proof.clone(env)?, env)?;
+    let participants = competition.get_participants(env)?;
+    competition.register(
+        user_token_proof,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(refund.amount(env)?, dec!(100));
+    assert!(participants.is_empty());
+    let portfolio = competition.get_user_portfolio(user_id, env)?;
+    assert_eq!(portfolio.total_value, dec!(10000));
+    Ok(())
+}
+
+#[test]
+fn test_cannot_unregister_after_competition_started() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    // Act
+    let result = competition.unregister(user_token_proof, env);
 
     // Assert
     assert!(result.is_err());
@@ -187,7 +597,7 @@ fn test_vault_can_trade_in_running_competition() -> Result<(), RuntimeError> {
         ..
     } = UnitTestEnvironment::new()?;
 
-    competition.register(user_token_proof.clone(env).unwrap(), env)?;
+    competition.register(user_token_proof.clone(env).unwrap(), None, None, env)?;
 
     let competition_start_time = competition.get_competition_start_time(env).unwrap();
     env.set_current_time(competition_start_time.add_days(2).unwrap());
@@ -218,7 +628,7 @@ fn test_vault_cannot_trade_before_competition_starts() -> Result<(), RuntimeErro
         ..
     } = UnitTestEnvironment::new()?;
 
-    competition.register(user_token_proof.clone(env).unwrap(), env)?;
+    competition.register(user_token_proof.clone(env).unwrap(), None, None, env)?;
 
     // Act
     let result = competition.trade(
@@ -246,7 +656,7 @@ fn test_vault_cannot_trade_after_competition_ends() -> Result<(), RuntimeError>
         ..
     } = UnitTestEnvironment::new()?;
 
-    competition.register(user_token_proof.clone(env).unwrap(), env)?;
+    competition.register(user_token_proof.clone(env).unwrap(), None, None, env)?;
 
     let competition_end_time = competition.get_competition_end_time(env).unwrap();
 
@@ -265,3 +675,3103 @@ fn test_vault_cannot_trade_after_competition_ends() -> Result<(), RuntimeError>
     assert!(result.is_err());
     Ok(())
 }
+
+#[test]
+fn test_first_trade_awards_achievement_badge() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_days(2).unwrap());
+
+    // Act
+    for _ in 0..2 {
+        competition.trade(
+            user_token_proof.clone(env)?,
+            resource_addresses.fusd,
+            resource_addresses.bitcoin,
+            Decimal::one(),
+            env,
+        )?;
+    }
+    let badges = competition.claim_achievements(user_token_proof.clone(env)?, env)?;
+    let badges_claimed_again = competition.claim_achievements(user_token_proof, env)?;
+
+    // Assert
+    assert_eq!(badges.amount(env)?, dec!(1));
+    assert_eq!(badges_claimed_again.amount(env)?, Decimal::zero());
+    Ok(())
+}
+
+#[test]
+fn test_no_achievement_badges_without_trading() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    // Act
+    let badges = competition.claim_achievements(user_token_proof, env)?;
+
+    // Assert
+    assert_eq!(badges.amount(env)?, Decimal::zero());
+    Ok(())
+}
+
+#[test]
+fn test_trades_are_recorded_in_trade_history() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_days(2).unwrap());
+
+    // Act
+    for amount in [dec!(10), dec!(20), dec!(30)] {
+        competition.trade(
+            user_token_proof.clone(env)?,
+            resource_addresses.fusd,
+            resource_addresses.bitcoin,
+            amount,
+            env,
+        )?;
+    }
+    let first_page = competition.get_trade_history(user_id.clone(), 0, 2, env)?;
+    let second_page = competition.get_trade_history(user_id.clone(), 2, 2, env)?;
+
+    // Assert
+    assert_eq!(competition.get_trade_count(user_id, env)?, 3);
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page[0].from_amount, dec!(10));
+    assert_eq!(first_page[1].from_amount, dec!(20));
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0].from_address, resource_addresses.fusd);
+    assert_eq!(second_page[0].to_address, resource_addresses.bitcoin);
+    assert_eq!(second_page[0].from_amount, dec!(30));
+    Ok(())
+}
+
+#[test]
+fn test_user_portfolio_is_valued_in_fusd() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    oracle.set_price(reference_addresses.fusd, dec!(2), env)?;
+    oracle.set_price(reference_addresses.bitcoin, dec!(200), env)?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_days(2).unwrap());
+
+    competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(1000),
+        env,
+    )?;
+
+    // Act
+    let portfolio = competition.get_user_portfolio(user_id, env)?;
+
+    // Assert
+    assert_eq!(portfolio.positions.len(), 2);
+    assert_eq!(
+        portfolio.positions[0].resource_address,
+        resource_addresses.fusd
+    );
+    assert_eq!(portfolio.positions[0].amount, dec!(9000));
+    assert_eq!(portfolio.positions[0].value, dec!(9000));
+    assert_eq!(
+        portfolio.positions[1].resource_address,
+        resource_addresses.bitcoin
+    );
+    assert_eq!(portfolio.positions[1].amount, dec!(10));
+    assert_eq!(portfolio.positions[1].price, dec!(100));
+    assert_eq!(portfolio.positions[1].value, dec!(1000));
+    assert_eq!(portfolio.total_value, dec!(10000));
+    Ok(())
+}
+
+#[test]
+fn test_ten_x_return_awards_achievement() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_days(2).unwrap());
+
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(1000),
+        env,
+    )?;
+
+    // Act
+    oracle.set_price(reference_addresses.bitcoin, dec!(100), env)?;
+    competition.trade(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        resource_addresses.fusd,
+        dec!(1),
+        env,
+    )?;
+
+    // Assert
+    let achievements = competition.get_user_achievements(user_id, env)?;
+    assert!(achievements.contains(&Achievement::TenXReturn));
+    Ok(())
+}
+
+#[test]
+fn test_snapshots_record_equity_curve_once_per_interval() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    competition.set_snapshot_interval(3600, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(5000),
+        env,
+    )?;
+
+    // Act
+    competition.take_snapshot(100, None, env)?;
+
+    oracle.set_price(reference_addresses.bitcoin, dec!(2), env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    competition.take_snapshot(100, None, env)?;
+
+    let equity_curve = competition.get_equity_curve(user_id, env)?;
+
+    env.set_current_time(competition_start_time.add_minutes(90).unwrap());
+    let snapshot_too_early = competition.take_snapshot(100, None, env);
+
+    // Assert
+    assert!(snapshot_too_early.is_err());
+    assert_eq!(equity_curve.len(), 2);
+    assert_eq!(equity_curve[0].timestamp, competition_start_time);
+    assert_eq!(equity_curve[0].value, dec!(10000));
+    assert_eq!(
+        equity_curve[1].timestamp,
+        competition_start_time.add_hours(1).unwrap()
+    );
+    assert_eq!(equity_curve[1].value, dec!(15000));
+    Ok(())
+}
+
+#[test]
+fn test_snapshots_are_taken_in_batches() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof, None, None, env)?;
+    competition.register(rival_token_proof, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    // Act
+    let (first_remaining, _) = competition.take_snapshot(1, None, env)?;
+    let pending_curve = competition.get_equity_curve(user_id.clone(), env)?;
+    let pending_snapshot_time = competition.get_last_snapshot_time(env)?;
+
+    env.set_current_time(competition_start_time.add_minutes(1).unwrap());
+    let (second_remaining, _) = competition.take_snapshot(1, None, env)?;
+
+    // Assert
+    assert_eq!(first_remaining, 1);
+    assert!(pending_curve.is_empty());
+    assert_eq!(pending_snapshot_time, None);
+    assert_eq!(second_remaining, 0);
+    assert_eq!(
+        competition.get_last_snapshot_time(env)?,
+        Some(competition_start_time)
+    );
+    for user_id in [user_id, rival_id] {
+        let equity_curve = competition.get_equity_curve(user_id, env)?;
+        assert_eq!(equity_curve.len(), 1);
+        assert_eq!(equity_curve[0].timestamp, competition_start_time);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_cannot_take_snapshot_before_competition_starts() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof, None, None, env)?;
+
+    // Act
+    let result = competition.take_snapshot(100, None, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_leaderboard_ranks_by_scoring_rule() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    competition.register(rival_token_proof, None, None, env)?;
+    competition.set_scoring_rule(ScoringRule::LowestRoi, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_days(1).unwrap());
+
+    competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(5000),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!(2), env)?;
+
+    // Act
+    let leaderboard = competition.get_leaderboard(env)?;
+
+    // Assert
+    assert_eq!(competition.get_scoring_rule(env)?, ScoringRule::LowestRoi);
+    assert_eq!(leaderboard.len(), 2);
+    assert_eq!(leaderboard[0].user_id, rival_id);
+    assert_eq!(leaderboard[0].portfolio_value, dec!(10000));
+    assert_eq!(leaderboard[0].score, Decimal::zero());
+    assert_eq!(leaderboard[1].user_id, user_id);
+    assert_eq!(leaderboard[1].portfolio_value, dec!(15000));
+    assert_eq!(leaderboard[1].score, dec!("-0.5"));
+    Ok(())
+}
+
+#[test]
+fn test_cannot_change_scoring_rule_after_competition_started() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    // Act
+    let result = competition.set_scoring_rule(ScoringRule::SharpeRatio, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_position_cannot_exceed_max_position_share() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_risk_rules(
+        RiskRules {
+            max_position_share: Some(dec!("0.5")),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(4000),
+        env,
+    )?;
+    let portfolio = competition.get_user_portfolio(user_id, env)?;
+
+    // Act
+    let result = competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(2000),
+        env,
+    );
+
+    // Assert
+    assert_eq!(portfolio.total_value, dec!(10000));
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_trade_below_min_trade_value_fails() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_risk_rules(
+        RiskRules {
+            min_trade_value: Some(dec!(100)),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(100),
+        env,
+    )?;
+    let trade_count = competition.get_trade_count(user_id, env)?;
+
+    // Act
+    let result = competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(99),
+        env,
+    );
+
+    // Assert
+    assert_eq!(trade_count, 1);
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_max_trades_per_day_resets_every_trading_day() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_risk_rules(
+        RiskRules {
+            max_trades_per_day: Some(1),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(10),
+        env,
+    )?;
+    env.set_current_time(competition_start_time.add_hours(25).unwrap());
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(10),
+        env,
+    )?;
+    let trade_count = competition.get_trade_count(user_id, env)?;
+
+    // Act
+    let result = competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(10),
+        env,
+    );
+
+    // Assert
+    assert_eq!(trade_count, 2);
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_cannot_set_invalid_risk_rules() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    // Act
+    let result = competition.set_risk_rules(
+        RiskRules {
+            max_position_share: Some(dec!("1.5")),
+            ..Default::default()
+        },
+        env,
+    );
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_trades_must_respect_cooldown() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_trade_protection(
+        TradeProtection {
+            cooldown: Some(60),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let first_trade_time = competition_start_time.add_hours(1).unwrap();
+    for trade_time in [first_trade_time, first_trade_time.add_seconds(60).unwrap()] {
+        env.set_current_time(trade_time);
+        competition.trade(
+            user_token_proof.clone(env)?,
+            resource_addresses.fusd,
+            resource_addresses.bitcoin,
+            dec!(10),
+            env,
+        )?;
+    }
+    let trade_count = competition.get_trade_count(user_id, env)?;
+
+    // Act
+    let result = competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(10),
+        env,
+    );
+
+    // Assert
+    assert_eq!(trade_count, 2);
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_perpetual_positions_must_respect_cooldown() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_trade_protection(
+        TradeProtection {
+            cooldown: Some(60),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.add_perpetual_market(
+        resource_addresses.bitcoin,
+        PerpetualMarketConfig {
+            max_leverage: dec!(10),
+            max_funding_rate: Decimal::zero(),
+            maintenance_margin: dec!("0.05"),
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let open_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(open_time);
+    competition.open_position(
+        user_token_proof.clone(env)?,
+        resource_addresses.bitcoin,
+        PositionSide::Long,
+        dec!(1000),
+        dec!(2),
+        env,
+    )?;
+
+    // Act
+    let close_result = competition.close_position(
+        user_token_proof.clone(env)?,
+        resource_addresses.bitcoin,
+        env,
+    );
+    let trade_result = competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.ethereum,
+        dec!(10),
+        env,
+    );
+    env.set_current_time(open_time.add_seconds(60).unwrap());
+    let later_close_result =
+        competition.close_position(user_token_proof, resource_addresses.bitcoin, env);
+
+    // Assert
+    assert!(close_result.is_err());
+    assert!(trade_result.is_err());
+    assert!(later_close_result.is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_cannot_trade_right_after_price_update() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        mut oracle,
+        reference_addresses,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_trade_protection(
+        TradeProtection {
+            min_price_age: Some(30),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let price_update_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(price_update_time);
+    oracle.set_price(reference_addresses.bitcoin, dec!(2), env)?;
+    env.set_current_time(price_update_time.add_seconds(30).unwrap());
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(10),
+        env,
+    )?;
+    let trade_count = competition.get_trade_count(user_id, env)?;
+    oracle.set_price(reference_addresses.bitcoin, dec!(3), env)?;
+
+    // Act
+    let result = competition.trade(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        resource_addresses.fusd,
+        dec!(1),
+        env,
+    );
+
+    // Assert
+    assert_eq!(trade_count, 1);
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_committed_trade_is_executed_when_revealed() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_trade_protection(
+        TradeProtection {
+            reveal_delay: Some(60),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let commit_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(commit_time);
+    let commitment = trade_commitment(
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(100),
+        "salt",
+    );
+    competition.commit_trade(user_token_proof.clone(env)?, commitment, env)?;
+    env.set_current_time(commit_time.add_seconds(60).unwrap());
+
+    // Act
+    competition.reveal_trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(100),
+        "salt".to_string(),
+        env,
+    )?;
+
+    // Assert
+    let trade_history = competition.get_trade_history(user_id.clone(), 0, 1, env)?;
+    assert_eq!(competition.get_trade_count(user_id, env)?, 1);
+    assert_eq!(trade_history[0].to_address, resource_addresses.bitcoin);
+    assert_eq!(trade_history[0].from_amount, dec!(100));
+    Ok(())
+}
+
+#[test]
+fn test_committed_trade_cannot_be_revealed_early() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_trade_protection(
+        TradeProtection {
+            reveal_delay: Some(60),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let commit_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(commit_time);
+    let commitment = trade_commitment(
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(100),
+        "salt",
+    );
+    competition.commit_trade(user_token_proof.clone(env)?, commitment, env)?;
+    env.set_current_time(commit_time.add_seconds(59).unwrap());
+
+    // Act
+    let result = competition.reveal_trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(100),
+        "salt".to_string(),
+        env,
+    );
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_committed_trade_expires_after_reveal_window() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_trade_protection(
+        TradeProtection {
+            reveal_delay: Some(60),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let commit_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(commit_time);
+    let commitment = trade_commitment(
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(100),
+        "salt",
+    );
+    competition.commit_trade(user_token_proof.clone(env)?, commitment, env)?;
+    env.set_current_time(commit_time.add_seconds(60 + REVEAL_WINDOW + 1).unwrap());
+
+    // Act
+    let result = competition.reveal_trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(100),
+        "salt".to_string(),
+        env,
+    );
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_perpetual_pnl_counts_towards_portfolio() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.add_perpetual_market(
+        resource_addresses.bitcoin,
+        PerpetualMarketConfig {
+            max_leverage: dec!(10),
+            max_funding_rate: Decimal::zero(),
+            maintenance_margin: dec!("0.05"),
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    competition.open_position(
+        user_token_proof.clone(env)?,
+        resource_addresses.bitcoin,
+        PositionSide::Long,
+        dec!(1000),
+        dec!(5),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!("1.1"), env)?;
+
+    // Act
+    let portfolio = competition.get_user_portfolio(user_id.clone(), env)?;
+    competition.close_position(user_token_proof, resource_addresses.bitcoin, env)?;
+
+    // Assert
+    assert_eq!(portfolio.perpetual_value, dec!(1500));
+    assert_eq!(portfolio.total_value, dec!(10500));
+    let portfolio = competition.get_user_portfolio(user_id.clone(), env)?;
+    assert_eq!(portfolio.positions[0].amount, dec!(10500));
+    assert!(competition
+        .get_perpetual_positions(user_id, env)?
+        .is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_perpetual_funding_is_paid_by_the_larger_side() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.add_perpetual_market(
+        resource_addresses.bitcoin,
+        PerpetualMarketConfig {
+            max_leverage: dec!(10),
+            max_funding_rate: dec!("0.01"),
+            maintenance_margin: dec!("0.05"),
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    competition.register(rival_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let open_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(open_time);
+    competition.open_position(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        PositionSide::Long,
+        dec!(1000),
+        dec!(3),
+        env,
+    )?;
+    competition.open_position(
+        rival_token_proof,
+        resource_addresses.bitcoin,
+        PositionSide::Short,
+        dec!(1000),
+        dec!(1),
+        env,
+    )?;
+
+    // Act
+    env.set_current_time(open_time.add_seconds(2 * FUNDING_INTERVAL).unwrap());
+    competition.apply_funding(resource_addresses.bitcoin, env)?;
+
+    // Assert
+    let market = competition.get_perpetual_market(resource_addresses.bitcoin, env)?;
+    assert_eq!(market.long_open_interest, dec!(3000));
+    assert_eq!(market.short_open_interest, dec!(1000));
+    assert_eq!(market.funding_index, dec!("0.01"));
+    let long_position = &competition.get_perpetual_positions(user_id, env)?[0];
+    let short_position = &competition.get_perpetual_positions(rival_id, env)?[0];
+    assert_eq!(long_position.funding_paid, dec!(30));
+    assert_eq!(long_position.equity, dec!(970));
+    assert_eq!(short_position.funding_paid, dec!(-10));
+    assert_eq!(short_position.equity, dec!(1010));
+    Ok(())
+}
+
+#[test]
+fn test_liquidated_user_does_not_survive_competition() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.add_perpetual_market(
+        resource_addresses.bitcoin,
+        PerpetualMarketConfig {
+            max_leverage: dec!(10),
+            max_funding_rate: Decimal::zero(),
+            maintenance_margin: dec!("0.05"),
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    competition.open_position(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        PositionSide::Long,
+        dec!(1000),
+        dec!(10),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!("0.94"), env)?;
+
+    // Act
+    competition.liquidate_position(user_id.clone(), resource_addresses.bitcoin, None, env)?;
+    env.set_current_time(competition_end_time);
+    competition.settle(100, env)?;
+
+    // Assert
+    let leaderboard = competition.get_leaderboard(env)?;
+    assert_eq!(leaderboard[0].portfolio_value, dec!(9000));
+    let achievements = competition.get_user_achievements(user_id, env)?;
+    assert!(achievements.contains(&Achievement::TopThreeFinish));
+    assert!(!achievements.contains(&Achievement::SurvivedCompetition));
+    Ok(())
+}
+
+#[test]
+fn test_healthy_perpetual_position_cannot_be_liquidated() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.add_perpetual_market(
+        resource_addresses.bitcoin,
+        PerpetualMarketConfig {
+            max_leverage: dec!(10),
+            max_funding_rate: Decimal::zero(),
+            maintenance_margin: dec!("0.05"),
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    competition.open_position(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        PositionSide::Long,
+        dec!(1000),
+        dec!(10),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!("0.96"), env)?;
+
+    // Act
+    let result = competition.liquidate_position(user_id, resource_addresses.bitcoin, None, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_liquidatable_position_cannot_be_closed() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.add_perpetual_market(
+        resource_addresses.bitcoin,
+        PerpetualMarketConfig {
+            max_leverage: dec!(10),
+            max_funding_rate: Decimal::zero(),
+            maintenance_margin: dec!("0.05"),
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    competition.open_position(
+        user_token_proof.clone(env)?,
+        resource_addresses.bitcoin,
+        PositionSide::Long,
+        dec!(1000),
+        dec!(10),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!("0.94"), env)?;
+
+    // Act
+    let result = competition.close_position(user_token_proof, resource_addresses.bitcoin, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_leveraged_notional_counts_towards_max_position_share() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.add_perpetual_market(
+        resource_addresses.bitcoin,
+        PerpetualMarketConfig {
+            max_leverage: dec!(10),
+            max_funding_rate: Decimal::zero(),
+            maintenance_margin: dec!("0.05"),
+        },
+        env,
+    )?;
+    competition.set_risk_rules(
+        RiskRules {
+            max_position_share: Some(dec!("0.5")),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    // Act
+    let result = competition.open_position(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        PositionSide::Long,
+        dec!(1000),
+        dec!(10),
+        env,
+    );
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_option_is_cash_settled_at_expiry() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_option_volatility(resource_addresses.bitcoin, dec!("0.8"), env)?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    let expiry = competition_start_time.add_days(1).unwrap();
+    let premium = competition.quote_option(
+        resource_addresses.bitcoin,
+        OptionKind::Call,
+        dec!(1),
+        expiry,
+        dec!(1000),
+        env,
+    )?;
+    let option_id = competition.buy_option(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        OptionKind::Call,
+        dec!(1),
+        expiry,
+        dec!(1000),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!("1.5"), env)?;
+    env.set_current_time(expiry);
+
+    // Act
+    let portfolio = competition.get_user_portfolio(user_id.clone(), env)?;
+    competition.settle_option(option_id, None, env)?;
+
+    // Assert
+    assert!(premium.is_positive());
+    assert_eq!(portfolio.option_value, dec!(500));
+    assert_eq!(portfolio.total_value, dec!(10500) - premium);
+    let portfolio = competition.get_user_portfolio(user_id.clone(), env)?;
+    assert_eq!(portfolio.positions[0].amount, dec!(10500) - premium);
+    assert!(competition.get_option_positions(user_id, env)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_option_cannot_be_settled_before_expiry() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_option_volatility(resource_addresses.bitcoin, dec!("0.8"), env)?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    let option_id = competition.buy_option(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        OptionKind::Put,
+        dec!(1),
+        competition_start_time.add_days(1).unwrap(),
+        dec!(1000),
+        env,
+    )?;
+
+    // Act
+    let result = competition.settle_option(option_id, None, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_option_is_settled_at_the_price_at_expiry() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_option_volatility(resource_addresses.bitcoin, dec!("0.8"), env)?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    let expiry = competition_start_time.add_days(1).unwrap();
+    let premium = competition.quote_option(
+        resource_addresses.bitcoin,
+        OptionKind::Call,
+        dec!(1),
+        expiry,
+        dec!(1000),
+        env,
+    )?;
+    let option_id = competition.buy_option(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        OptionKind::Call,
+        dec!(1),
+        expiry,
+        dec!(1000),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!("1.5"), env)?;
+    env.set_current_time(expiry.add_hours(2).unwrap());
+    oracle.set_price(reference_addresses.bitcoin, dec!(3), env)?;
+
+    // Act
+    let portfolio = competition.get_user_portfolio(user_id.clone(), env)?;
+    competition.settle_option(option_id, None, env)?;
+
+    // Assert
+    assert_eq!(portfolio.option_value, dec!(500));
+    let portfolio = competition.get_user_portfolio(user_id.clone(), env)?;
+    assert_eq!(portfolio.positions[0].amount, dec!(10500) - premium);
+    assert!(competition.get_option_positions(user_id, env)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_option_premium_below_min_trade_value_fails() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_option_volatility(resource_addresses.bitcoin, dec!("0.8"), env)?;
+    competition.set_risk_rules(
+        RiskRules {
+            min_trade_value: Some(dec!(100)),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    // Act
+    let result = competition.buy_option(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        OptionKind::Call,
+        dec!(1),
+        competition_start_time.add_days(1).unwrap(),
+        dec!(1),
+        env,
+    );
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_option_cannot_expire_after_competition_end() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_option_volatility(resource_addresses.bitcoin, dec!("0.8"), env)?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    // Act
+    let result = competition.buy_option(
+        user_token_proof,
+        resource_addresses.bitcoin,
+        OptionKind::Call,
+        dec!(1),
+        competition_end_time.add_seconds(1).unwrap(),
+        dec!(1000),
+        env,
+    );
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_dca_order_buys_every_interval() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let schedule_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(schedule_time);
+    let order_id = competition.schedule_dca_order(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.ethereum,
+        dec!(100),
+        6 * 60 * 60,
+        env,
+    )?;
+
+    // Act
+    competition.execute_scheduled_order(order_id, None, env)?;
+    let early_result = competition.execute_scheduled_order(order_id, None, env);
+    env.set_current_time(schedule_time.add_hours(6).unwrap());
+    competition.execute_scheduled_order(order_id, None, env)?;
+
+    // Assert
+    assert!(early_result.is_err());
+    let portfolio = competition.get_user_portfolio(user_id.clone(), env)?;
+    assert_eq!(portfolio.positions[0].amount, dec!(9800));
+    assert_eq!(portfolio.positions[1].amount, dec!(200));
+    let order = competition.get_scheduled_order(order_id, env)?;
+    assert_eq!(order.next_execution, schedule_time.add_hours(12).unwrap());
+    assert_eq!(competition.get_scheduled_orders(user_id, env)?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_late_order_slice_does_not_catch_up_on_missed_intervals() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let schedule_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(schedule_time);
+    let order_id = competition.schedule_dca_order(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.ethereum,
+        dec!(100),
+        6 * 60 * 60,
+        env,
+    )?;
+    let late_time = schedule_time.add_hours(20).unwrap();
+    env.set_current_time(late_time);
+
+    // Act
+    competition.execute_scheduled_order(order_id, None, env)?;
+    let result = competition.execute_scheduled_order(order_id, None, env);
+
+    // Assert
+    assert!(result.is_err());
+    let portfolio = competition.get_user_portfolio(user_id, env)?;
+    assert_eq!(portfolio.positions[1].amount, dec!(100));
+    let order = competition.get_scheduled_order(order_id, env)?;
+    assert_eq!(order.next_execution, late_time.add_hours(6).unwrap());
+    Ok(())
+}
+
+#[test]
+fn test_twap_order_slices_are_bounded_by_balance() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let schedule_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(schedule_time);
+    let order_id = competition.schedule_twap_order(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(15000),
+        3,
+        60 * 60,
+        env,
+    )?;
+
+    // Act
+    for hour in 0..3 {
+        env.set_current_time(schedule_time.add_hours(hour).unwrap());
+        competition.execute_scheduled_order(order_id, None, env)?;
+    }
+
+    // Assert
+    let portfolio = competition.get_user_portfolio(user_id.clone(), env)?;
+    assert_eq!(portfolio.positions.len(), 1);
+    assert_eq!(
+        portfolio.positions[0].resource_address,
+        resource_addresses.bitcoin
+    );
+    assert_eq!(portfolio.positions[0].amount, dec!(10000));
+    assert!(competition.get_scheduled_orders(user_id, env)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_cancelled_order_cannot_be_executed() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    let order_id = competition.schedule_dca_order(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.ethereum,
+        dec!(100),
+        6 * 60 * 60,
+        env,
+    )?;
+    competition.cancel_scheduled_order(user_token_proof, order_id, env)?;
+
+    // Act
+    let result = competition.execute_scheduled_order(order_id, None, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_keeper_rewards_are_limited_per_day() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let xrd = ResourceManager(XRD).mint_fungible(dec!(10), env)?;
+    competition.fund_keeper_rewards(xrd, env)?;
+    competition.set_keeper_reward_config(
+        KeeperRewardConfig {
+            reward: dec!(2),
+            max_rewards_per_day: Some(1),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+
+    // Act
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    let (_, first_reward) =
+        competition.take_snapshot(100, Some(rival_token_proof.clone(env)?), env)?;
+    env.set_current_time(competition_start_time.add_hours(2).unwrap());
+    let (_, second_reward) =
+        competition.take_snapshot(100, Some(rival_token_proof.clone(env)?), env)?;
+    env.set_current_time(competition_start_time.add_days(1).unwrap());
+    let (_, next_day_reward) = competition.take_snapshot(100, Some(rival_token_proof), env)?;
+
+    // Assert
+    assert_eq!(first_reward.amount(env)?, dec!(2));
+    assert_eq!(second_reward.amount(env)?, Decimal::zero());
+    assert_eq!(next_day_reward.amount(env)?, dec!(2));
+    assert_eq!(competition.get_keeper_reward_balance(env)?, dec!(6));
+    Ok(())
+}
+
+#[test]
+fn test_skipped_order_slice_is_not_rewarded() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let xrd = ResourceManager(XRD).mint_fungible(dec!(10), env)?;
+    competition.fund_keeper_rewards(xrd, env)?;
+    competition.set_keeper_reward_config(
+        KeeperRewardConfig {
+            reward: Decimal::one(),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let schedule_time = competition_start_time.add_hours(1).unwrap();
+    env.set_current_time(schedule_time);
+    let order_id = competition.schedule_twap_order(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(20000),
+        2,
+        60 * 60,
+        env,
+    )?;
+
+    // Act
+    let executed_reward =
+        competition.execute_scheduled_order(order_id, Some(rival_token_proof.clone(env)?), env)?;
+    env.set_current_time(schedule_time.add_hours(1).unwrap());
+    let skipped_reward =
+        competition.execute_scheduled_order(order_id, Some(rival_token_proof), env)?;
+
+    // Assert
+    assert_eq!(executed_reward.amount(env)?, Decimal::one());
+    assert_eq!(skipped_reward.amount(env)?, Decimal::zero());
+    assert_eq!(competition.get_keeper_reward_balance(env)?, dec!(9));
+    Ok(())
+}
+
+#[test]
+fn test_keeper_is_not_rewarded_for_own_or_small_order_slices() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let xrd = ResourceManager(XRD).mint_fungible(dec!(10), env)?;
+    competition.fund_keeper_rewards(xrd, env)?;
+    competition.set_keeper_reward_config(
+        KeeperRewardConfig {
+            reward: Decimal::one(),
+            ..Default::default()
+        },
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    let large_order_id = competition.schedule_dca_order(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(1000),
+        60 * 60,
+        env,
+    )?;
+    let small_order_id = competition.schedule_dca_order(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(10),
+        60 * 60,
+        env,
+    )?;
+
+    // Act
+    let own_reward = competition.execute_scheduled_order(
+        large_order_id,
+        Some(user_token_proof.clone(env)?),
+        env,
+    )?;
+    let small_reward =
+        competition.execute_scheduled_order(small_order_id, Some(rival_token_proof), env)?;
+
+    // Assert
+    assert_eq!(own_reward.amount(env)?, Decimal::zero());
+    assert_eq!(small_reward.amount(env)?, Decimal::zero());
+    assert_eq!(competition.get_keeper_reward_balance(env)?, dec!(10));
+    Ok(())
+}
+
+#[test]
+fn test_open_orders_per_user_are_limited() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+    for _ in 0..MAX_OPEN_ORDERS {
+        competition.schedule_dca_order(
+            user_token_proof.clone(env)?,
+            resource_addresses.fusd,
+            resource_addresses.bitcoin,
+            dec!(100),
+            60 * 60,
+            env,
+        )?;
+    }
+
+    // Act
+    let result = competition.schedule_dca_order(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(100),
+        60 * 60,
+        env,
+    );
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_leader_trade_is_mirrored_in_proportion() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    competition.register(rival_token_proof.clone(env)?, None, None, env)?;
+    competition.follow(
+        rival_token_proof,
+        user_id.clone(),
+        CopySettings {
+            copy_ratio: dec!("0.5"),
+            max_trade_value: None,
+            max_profit_share: Decimal::zero(),
+        },
+        env,
+    )?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    // Act
+    competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(4000),
+        env,
+    )?;
+
+    // Assert
+    let portfolio = competition.get_user_portfolio(rival_id.clone(), env)?;
+    assert_eq!(portfolio.positions[0].amount, dec!(8000));
+    assert_eq!(
+        portfolio.positions[1].resource_address,
+        resource_addresses.bitcoin
+    );
+    assert_eq!(portfolio.positions[1].amount, dec!(2000));
+    assert_eq!(competition.get_trade_count(rival_id.clone(), env)?, 1);
+    assert_eq!(competition.get_followers(user_id, env)?, vec![rival_id]);
+    Ok(())
+}
+
+#[test]
+fn test_unfollowed_user_is_not_mirrored() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    competition.register(rival_token_proof.clone(env)?, None, None, env)?;
+    competition.follow(
+        rival_token_proof.clone(env)?,
+        user_id.clone(),
+        CopySettings {
+            copy_ratio: Decimal::one(),
+            max_trade_value: None,
+            max_profit_share: Decimal::zero(),
+        },
+        env,
+    )?;
+    competition.unfollow(rival_token_proof, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    // Act
+    competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(4000),
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(competition.get_trade_count(rival_id.clone(), env)?, 0);
+    assert!(competition.get_follow(rival_id, env)?.is_none());
+    assert!(competition.get_followers(user_id, env)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_leader_receives_profit_share_of_follower_prize() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let entry_fee_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let entry_fee_address = entry_fee_tokens.resource_address(env)?;
+    competition.set_entry_fee(entry_fee_address, dec!(100), env)?;
+    competition.set_payout_table(vec![dec!("0.7"), dec!("0.3")], env)?;
+
+    competition.register(
+        user_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+    competition.register(
+        rival_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+    competition.set_profit_share(user_token_proof.clone(env)?, dec!("0.1"), env)?;
+    competition.follow(
+        rival_token_proof.clone(env)?,
+        user_id,
+        CopySettings {
+            copy_ratio: dec!("0.5"),
+            max_trade_value: None,
+            max_profit_share: dec!("0.2"),
+        },
+        env,
+    )?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(5000),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!(2), env)?;
+    env.set_current_time(competition_end_time);
+
+    // Act
+    competition.settle(100, env)?;
+    let user_prize = competition.claim_prize(user_token_proof, env)?;
+    let rival_prize = competition.claim_prize(rival_token_proof, env)?;
+
+    // Assert
+    assert_eq!(user_prize.len(), 2);
+    assert_eq!(user_prize[0].amount(env)?, dec!(140));
+    assert_eq!(user_prize[1].amount(env)?, dec!(6));
+    assert_eq!(rival_prize[0].amount(env)?, dec!(54));
+    Ok(())
+}
+
+#[test]
+fn test_profit_share_is_owed_for_each_mirrored_trade() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let entry_fee_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let entry_fee_address = entry_fee_tokens.resource_address(env)?;
+    competition.set_entry_fee(entry_fee_address, dec!(100), env)?;
+    competition.set_payout_table(vec![dec!("0.7"), dec!("0.3")], env)?;
+
+    competition.register(
+        user_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+    competition.register(
+        rival_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+    let settings = CopySettings {
+        copy_ratio: dec!("0.5"),
+        max_trade_value: None,
+        max_profit_share: dec!("0.2"),
+    };
+    competition.set_profit_share(user_token_proof.clone(env)?, dec!("0.1"), env)?;
+    competition.follow(
+        rival_token_proof.clone(env)?,
+        user_id.clone(),
+        settings.clone(),
+        env,
+    )?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_start_time);
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(5000),
+        env,
+    )?;
+
+    // The follower switches to the same leader without a profit share
+    competition.unfollow(rival_token_proof.clone(env)?, env)?;
+    competition.set_profit_share(user_token_proof.clone(env)?, Decimal::zero(), env)?;
+    competition.follow(rival_token_proof.clone(env)?, user_id, settings, env)?;
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(5000),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!(2), env)?;
+    env.set_current_time(competition_end_time);
+
+    // Act
+    competition.settle(100, env)?;
+    let user_prize = competition.claim_prize(user_token_proof, env)?;
+    let rival_prize = competition.claim_prize(rival_token_proof, env)?;
+
+    // Assert
+    assert_eq!(user_prize.len(), 2);
+    assert_eq!(user_prize[0].amount(env)?, dec!(140));
+    assert_eq!(user_prize[1].amount(env)?, dec!(3));
+    assert_eq!(rival_prize[0].amount(env)?, dec!(57));
+    Ok(())
+}
+
+#[test]
+fn test_spectator_claims_bet_winnings_after_settlement() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(rival_token_proof, None, None, env)?;
+    let stake = ResourceManager(XRD).mint_fungible(dec!(10), env)?;
+    competition.place_bet(user_token_proof.clone(env)?, rival_id.clone(), stake, env)?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time);
+
+    // Act
+    competition.settle(100, env)?;
+    let winnings = competition.claim_bet_winnings(user_token_proof, env)?;
+
+    // Assert
+    assert_eq!(winnings.amount(env)?, dec!(10));
+    assert_eq!(
+        competition.get_bet_pools(env)?,
+        vec![(rival_id.clone(), dec!(10))]
+    );
+    let betting_result = competition.get_betting_result(env)?.unwrap();
+    assert_eq!(betting_result.winner_id, rival_id);
+    assert!(competition.get_bets(user_id, env)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_bets_are_refunded_when_settled_without_players() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(rival_token_proof.clone(env)?, None, None, env)?;
+    let stake = ResourceManager(XRD).mint_fungible(dec!(10), env)?;
+    competition.place_bet(user_token_proof.clone(env)?, rival_id, stake, env)?;
+    competition.unregister(rival_token_proof, env)?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time);
+    competition.settle(100, env)?;
+
+    // Act
+    let refund = competition.claim_bet_winnings(user_token_proof, env)?;
+
+    // Assert
+    assert!(competition.get_betting_result(env)?.is_none());
+    assert_eq!(refund.amount(env)?, dec!(10));
+    Ok(())
+}
+
+#[test]
+fn test_betting_closes_at_competition_start() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(rival_token_proof, None, None, env)?;
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    // Act
+    let stake = ResourceManager(XRD).mint_fungible(dec!(10), env)?;
+    let result = competition.place_bet(user_token_proof, rival_id, stake, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_cannot_change_betting_close_time_after_betting_closed() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    // Act
+    let result = competition.set_betting_close_time(competition_end_time, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_player_cannot_bet_on_own_competition() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    competition.register(rival_token_proof, None, None, env)?;
+
+    // Act
+    let stake = ResourceManager(XRD).mint_fungible(dec!(10), env)?;
+    let result = competition.place_bet(user_token_proof, rival_id, stake, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_spectator_with_bets_cannot_register() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(rival_token_proof, None, None, env)?;
+    let stake = ResourceManager(XRD).mint_fungible(dec!(10), env)?;
+    competition.place_bet(user_token_proof.clone(env)?, rival_id, stake, env)?;
+
+    // Act
+    let result = competition.register(user_token_proof, None, None, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_referrer_claims_share_of_entry_fee() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let entry_fee_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let entry_fee_address = entry_fee_tokens.resource_address(env)?;
+    competition.set_entry_fee(entry_fee_address, dec!(100), env)?;
+    competition.set_referral_config(
+        ReferralConfig {
+            fee_share: dec!("0.1"),
+            bonus_balance: Decimal::zero(),
+        },
+        env,
+    )?;
+
+    competition.register(
+        user_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+    competition.register(
+        rival_token_proof,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        Some(user_id.clone()),
+        env,
+    )?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time);
+
+    // Act
+    competition.settle(100, env)?;
+    let referral_fees = competition.claim_referral_fees(user_token_proof.clone(env)?, env)?;
+    let prize = competition.claim_prize(user_token_proof, env)?;
+
+    // Assert
+    assert_eq!(referral_fees.amount(env)?, dec!(10));
+    assert_eq!(prize[0].amount(env)?, dec!(190));
+    assert_eq!(
+        competition.get_referrer(rival_id, env)?,
+        Some(user_id.clone())
+    );
+    let referrer_stats = competition.get_referrer_stats(env)?;
+    assert_eq!(referrer_stats[0].0, user_id);
+    assert_eq!(referrer_stats[0].1.referral_count, 1);
+    assert_eq!(referrer_stats[0].1.fees_claimed, dec!(10));
+    Ok(())
+}
+
+#[test]
+fn test_unregistered_referrer_cannot_claim_referral_fees() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let entry_fee_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let entry_fee_address = entry_fee_tokens.resource_address(env)?;
+    competition.set_entry_fee(entry_fee_address, dec!(100), env)?;
+    competition.set_referral_config(
+        ReferralConfig {
+            fee_share: dec!("0.1"),
+            bonus_balance: Decimal::zero(),
+        },
+        env,
+    )?;
+
+    competition.register(
+        user_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+    competition.register(
+        rival_token_proof,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        Some(user_id),
+        env,
+    )?;
+    competition.unregister(user_token_proof.clone(env)?, env)?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time);
+    competition.settle(100, env)?;
+
+    // Act
+    let result = competition.claim_referral_fees(user_token_proof, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_referral_bonus_does_not_count_towards_score() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.set_referral_config(
+        ReferralConfig {
+            fee_share: Decimal::zero(),
+            bonus_balance: dec!(1000),
+        },
+        env,
+    )?;
+    competition.register(user_token_proof, None, None, env)?;
+
+    // Act
+    competition.register(rival_token_proof, None, Some(user_id.clone()), env)?;
+
+    // Assert
+    let portfolio = competition.get_user_portfolio(user_id.clone(), env)?;
+    assert_eq!(portfolio.total_value, dec!(11000));
+    let referrer_stats = competition.get_referrer_stats(env)?;
+    assert_eq!(referrer_stats[0].1.bonus_balance, dec!(1000));
+    let leaderboard = competition.get_leaderboard(env)?;
+    let referrer_entry = leaderboard
+        .iter()
+        .find(|entry| entry.user_id == user_id)
+        .unwrap();
+    assert_eq!(referrer_entry.portfolio_value, dec!(10000));
+    assert_eq!(referrer_entry.score, Decimal::zero());
+    Ok(())
+}
+
+#[test]
+fn test_self_and_circular_referrals_are_rejected() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    competition.register(rival_token_proof, None, Some(user_id.clone()), env)?;
+    competition.unregister(user_token_proof.clone(env)?, env)?;
+
+    // Act
+    let self_referral_result = competition.register(
+        user_token_proof.clone(env)?,
+        None,
+        Some(user_id.clone()),
+        env,
+    );
+    let circular_referral_result =
+        competition.register(user_token_proof, None, Some(rival_id), env);
+
+    // Assert
+    assert!(self_referral_result.is_err());
+    assert!(circular_referral_result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_referrer_must_be_registered() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    // Act
+    let result = competition.register(user_token_proof, None, Some(rival_id), env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_sponsor_tokens_are_split_by_payout_table() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        rival_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let sponsor_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let sponsor_token_address = sponsor_tokens.resource_address(env)?;
+    let sponsor_id = competition.add_sponsor(
+        SponsorInfo {
+            name: "Radix Partner".to_string(),
+            logo_url: "https://example.com/logo.png".to_string(),
+            website_url: None,
+        },
+        vec![sponsor_token_address],
+        env,
+    )?;
+    competition.sponsor_prize_pool(sponsor_id, sponsor_tokens.into(), env)?;
+    competition.set_payout_table(vec![dec!("0.7"), dec!("0.3")], env)?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    competition.register(rival_token_proof.clone(env)?, None, None, env)?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time);
+
+    // Act
+    competition.settle(100, env)?;
+    let user_prize = competition.claim_prize(user_token_proof, env)?;
+    let rival_prize = competition.claim_prize(rival_token_proof, env)?;
+
+    // Assert
+    assert_eq!(user_prize[0].resource_address(env)?, sponsor_token_address);
+    assert_eq!(user_prize[0].amount(env)?, dec!(700));
+    assert_eq!(rival_prize[0].amount(env)?, dec!(300));
+    let sponsors = competition.get_sponsors(env)?;
+    assert_eq!(sponsors[0].1.info.name, "Radix Partner");
+    assert_eq!(
+        sponsors[0].1.contributions,
+        vec![(sponsor_token_address, dec!(1000))]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sponsor_pool_locks_at_competition_start() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let sponsor_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let sponsor_token_address = sponsor_tokens.resource_address(env)?;
+    let sponsor_id = competition.add_sponsor(
+        SponsorInfo {
+            name: "Radix Partner".to_string(),
+            logo_url: "https://example.com/logo.png".to_string(),
+            website_url: None,
+        },
+        vec![sponsor_token_address],
+        env,
+    )?;
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    // Act
+    let result = competition.sponsor_prize_pool(sponsor_id, sponsor_tokens.into(), env);
+
+    // Assert
+    assert!(competition.is_sponsor_pool_locked(env)?);
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_sponsor_cannot_deposit_resource_not_allowed_by_admin() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let sponsor_id = competition.add_sponsor(
+        SponsorInfo {
+            name: "Radix Partner".to_string(),
+            logo_url: "https://example.com/logo.png".to_string(),
+            website_url: None,
+        },
+        vec![XRD],
+        env,
+    )?;
+    let junk_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+
+    // Act
+    let result = competition.sponsor_prize_pool(sponsor_id, junk_tokens.into(), env);
+
+    // Assert
+    assert!(result.is_err());
+    assert!(competition.get_prize_pools(env)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_sponsor_contributions_are_returned_when_settled_without_players() -> Result<(), RuntimeError>
+{
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let sponsor_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let sponsor_token_address = sponsor_tokens.resource_address(env)?;
+    let sponsor_id = competition.add_sponsor(
+        SponsorInfo {
+            name: "Radix Partner".to_string(),
+            logo_url: "https://example.com/logo.png".to_string(),
+            website_url: None,
+        },
+        vec![sponsor_token_address],
+        env,
+    )?;
+    competition.sponsor_prize_pool(sponsor_id, sponsor_tokens.into(), env)?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time);
+    competition.settle(100, env)?;
+
+    // Act
+    let returned = competition.return_sponsor_contributions(sponsor_id, env)?;
+
+    // Assert
+    assert_eq!(returned[0].resource_address(env)?, sponsor_token_address);
+    assert_eq!(returned[0].amount(env)?, dec!(1000));
+    Ok(())
+}
+
+#[test]
+fn test_season_awards_points_and_prize_from_settled_competition() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        package_address,
+        trade_simulator,
+        reference_addresses,
+        user_token_address,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let (mut season, _) = Season::instantiate(
+        OwnerRole::None,
+        "Season 3".to_string(),
+        user_token_address,
+        XRD,
+        package_address,
+        env,
+    )?;
+    let prize = ResourceManager(XRD).mint_fungible(dec!(100), env)?;
+    season.fund_prize_pool(prize.into(), env)?;
+
+    let registration_start = env.get_current_time();
+    let competition_start = registration_start.add_days(1).unwrap();
+    let competition_end = competition_start.add_days(7).unwrap();
+    let (mut competition, _) = season.create_competition(
+        "Week 1".to_string(),
+        registration_start,
+        competition_start,
+        competition_start,
+        competition_end,
+        trade_simulator.try_into().unwrap(),
+        reference_addresses.fusd,
+        env,
+    )?;
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    env.set_current_time(competition_end);
+
+    // Act
+    competition.settle(100, env)?;
+    season.end_season(env)?;
+    let season_prize = season.claim_prize(user_token_proof, env)?;
+
+    // Assert
+    assert_eq!(season.get_season_points(user_id.clone(), env)?, 25);
+    assert_eq!(season.get_leaderboard(env)?[0].user_id, user_id);
+    assert_eq!(season_prize.amount(env)?, dec!(100));
+    Ok(())
+}
+
+#[test]
+fn test_season_cannot_end_before_its_competitions_finish() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        package_address,
+        trade_simulator,
+        reference_addresses,
+        user_token_address,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let (mut season, _) = Season::instantiate(
+        OwnerRole::None,
+        "Season 3".to_string(),
+        user_token_address,
+        XRD,
+        package_address,
+        env,
+    )?;
+    let registration_start = env.get_current_time();
+    let competition_start = registration_start.add_days(1).unwrap();
+    season.create_competition(
+        "Week 1".to_string(),
+        registration_start,
+        competition_start,
+        competition_start,
+        competition_start.add_days(7).unwrap(),
+        trade_simulator.try_into().unwrap(),
+        reference_addresses.fusd,
+        env,
+    )?;
+
+    // Act
+    let result = season.end_season(env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_season_rejects_results_without_season_badge() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        package_address,
+        user_token_address,
+        user_token_proof,
+        user_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let (mut season, _) = Season::instantiate(
+        OwnerRole::None,
+        "Season 3".to_string(),
+        user_token_address,
+        XRD,
+        package_address,
+        env,
+    )?;
+
+    // Act
+    let result = season.report_results(user_token_proof, vec![user_id], env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_competition_rejects_badge_not_issued_by_a_season() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        trade_simulator,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let forged_badge = ResourceBuilder::new_integer_non_fungible(OwnerRole::None)
+        .mint_initial_supply(
+            [(
+                1u64.into(),
+                SeasonBadge {
+                    season_address: trade_simulator.try_into().unwrap(),
+                    competition_name: "Forged".to_string(),
+                },
+            )],
+            env,
+        )?;
+
+    // Act
+    let result = competition.join_season(forged_badge, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_competition_status_follows_schedule() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+
+    // Act
+    let registration_status = competition.get_status(env)?;
+    env.set_current_time(competition_start_time);
+    let running_status = competition.get_status(env)?;
+    env.set_current_time(competition_end_time);
+    let ended_status = competition.get_status(env)?;
+    competition.settle(100, env)?;
+    let settled_status = competition.get_status(env)?;
+
+    // Assert
+    assert_eq!(registration_status, CompetitionStatus::RegistrationOpen);
+    assert_eq!(running_status, CompetitionStatus::Running);
+    assert_eq!(ended_status, CompetitionStatus::Ended);
+    assert_eq!(settled_status, CompetitionStatus::Settled);
+    Ok(())
+}
+
+#[test]
+fn test_cannot_change_competition_times_once_running() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    // Act
+    let result =
+        competition.set_competition_end_time(competition_end_time.add_days(1).unwrap(), env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_competition_end_must_come_after_start() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+
+    // Act
+    let result = competition.set_competition_end_time(competition_start_time, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_cancelled_competition_refunds_entry_fee() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let entry_fee_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let entry_fee_address = entry_fee_tokens.resource_address(env)?;
+    competition.set_entry_fee(entry_fee_address, dec!(100), env)?;
+
+    let change = competition
+        .register(
+            user_token_proof.clone(env)?,
+            Some(entry_fee_tokens.take(dec!(150), env)?.into()),
+            None,
+            env,
+        )?
+        .unwrap();
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time.add_hours(1).unwrap());
+
+    // Act
+    competition.cancel(env)?;
+    let refund = competition.claim_refund(user_token_proof.clone(env)?, env)?;
+    let second_refund = competition.claim_refund(user_token_proof.clone(env)?, env)?;
+    let status = competition.get_status(env)?;
+    let (change_amount, refund_amount, second_refund_amount) = (
+        change.amount(env)?,
+        refund.amount(env)?,
+        second_refund.amount(env)?,
+    );
+    let trade_result = competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(1),
+        env,
+    );
+
+    // Assert
+    assert_eq!(change_amount, dec!(50));
+    assert_eq!(refund_amount, dec!(100));
+    assert_eq!(second_refund_amount, Decimal::zero());
+    assert_eq!(status, CompetitionStatus::Cancelled);
+    assert!(trade_result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_settlement_pays_prizes_by_payout_table() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let entry_fee_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let entry_fee_address = entry_fee_tokens.resource_address(env)?;
+    competition.set_entry_fee(entry_fee_address, dec!(100), env)?;
+    competition.set_payout_table(vec![dec!("0.7"), dec!("0.3")], env)?;
+
+    competition.register(
+        user_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+    competition.register(
+        rival_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    competition.trade(
+        user_token_proof.clone(env)?,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(5000),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!(2), env)?;
+    env.set_current_time(competition_end_time);
+
+    // Act
+    competition.settle(100, env)?;
+    let user_prize = competition.claim_prize(user_token_proof, env)?;
+    let rival_prize = competition.claim_prize(rival_token_proof, env)?;
+
+    // Assert
+    let leaderboard = competition.get_leaderboard(env)?;
+    assert_eq!(leaderboard[0].user_id, user_id);
+    assert_eq!(leaderboard[1].user_id, rival_id);
+    assert_eq!(user_prize.len(), 1);
+    assert_eq!(user_prize[0].amount(env)?, dec!(140));
+    assert_eq!(rival_prize[0].amount(env)?, dec!(60));
+    assert_eq!(
+        competition.get_prize_pools(env)?,
+        vec![(entry_fee_address, Decimal::zero())]
+    );
+    let achievements = competition.get_user_achievements(rival_id, env)?;
+    assert!(achievements.contains(&Achievement::TopThreeFinish));
+    assert!(achievements.contains(&Achievement::SurvivedCompetition));
+    Ok(())
+}
+
+#[test]
+fn test_vesting_prize_is_withdrawn_as_it_unlocks() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let entry_fee_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let entry_fee_address = entry_fee_tokens.resource_address(env)?;
+    competition.set_entry_fee(entry_fee_address, dec!(100), env)?;
+    competition.set_prize_vesting(
+        Some(VestingSchedule {
+            cliff_share: dec!("0.2"),
+            duration: 30 * 24 * 60 * 60,
+        }),
+        env,
+    )?;
+    competition.register(
+        user_token_proof.clone(env)?,
+        Some(entry_fee_tokens.take(dec!(100), env)?.into()),
+        None,
+        env,
+    )?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time);
+    competition.settle(100, env)?;
+    let prize_claim = competition.claim_prize(user_token_proof, env)?.remove(0);
+
+    // Act
+    let cliff = competition.withdraw_vested_prize(prize_claim.create_proof_of_all(env)?, env)?;
+    env.set_current_time(competition_end_time.add_days(15).unwrap());
+    let unlocked = competition.withdraw_vested_prize(prize_claim.create_proof_of_all(env)?, env)?;
+    let result = competition.withdraw_vested_prize(prize_claim.create_proof_of_all(env)?, env);
+
+    // Assert
+    assert_eq!(
+        prize_claim.resource_address(env)?,
+        competition.get_prize_claim_address(env)?
+    );
+    assert_eq!(cliff.amount(env)?, dec!(20));
+    assert_eq!(unlocked.amount(env)?, dec!(40));
+    assert!(result.is_err());
+    assert_eq!(
+        competition.get_prize_pools(env)?,
+        vec![(entry_fee_address, dec!(40))]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_settlement_is_completed_over_several_batches() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut oracle,
+        reference_addresses,
+        mut competition,
+        resource_addresses,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof.clone(env)?, None, None, env)?;
+    competition.register(rival_token_proof, None, None, env)?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_start_time);
+    competition.trade(
+        user_token_proof,
+        resource_addresses.fusd,
+        resource_addresses.bitcoin,
+        dec!(5000),
+        env,
+    )?;
+    oracle.set_price(reference_addresses.bitcoin, dec!(2), env)?;
+    env.set_current_time(competition_end_time);
+
+    // Act
+    let remaining_after_first_batch = competition.settle(1, env)?;
+    let status_after_first_batch = competition.get_status(env)?;
+    let remaining_after_second_batch = competition.settle(2, env)?;
+    let cancel_result = competition.cancel(env);
+    let remaining_after_third_batch = competition.settle(2, env)?;
+
+    // Assert
+    assert_eq!(remaining_after_first_batch, 3);
+    assert_eq!(status_after_first_batch, CompetitionStatus::Ended);
+    assert_eq!(remaining_after_second_batch, 1);
+    assert!(cancel_result.is_err());
+    assert_eq!(remaining_after_third_batch, 0);
+    assert_eq!(competition.get_status(env)?, CompetitionStatus::Settled);
+    let leaderboard = competition.get_leaderboard(env)?;
+    assert_eq!(leaderboard[0].user_id, user_id);
+    assert_eq!(leaderboard[1].user_id, rival_id);
+    assert!(competition
+        .get_user_achievements(rival_id, env)?
+        .contains(&Achievement::SurvivedCompetition));
+    Ok(())
+}
+
+#[test]
+fn test_cannot_settle_before_competition_ends() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let competition_start_time = competition.get_competition_start_time(env)?;
+    env.set_current_time(competition_start_time);
+
+    // Act
+    let result = competition.settle(100, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_cannot_settle_after_settlement_window() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time.add_hours(1).unwrap());
+
+    // Act
+    let result = competition.settle(100, env);
+    competition.cancel(env)?;
+
+    // Assert
+    assert!(result.is_err());
+    assert_eq!(competition.get_status(env)?, CompetitionStatus::Cancelled);
+    Ok(())
+}
+
+#[test]
+fn test_virtual_assets_are_burned_in_batches_after_settlement() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        user_id,
+        rival_token_proof,
+        rival_id,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof, None, None, env)?;
+    competition.register(rival_token_proof, None, None, env)?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time);
+    competition.settle(100, env)?;
+
+    // Act
+    let remaining_after_first_batch = competition.burn_virtual_assets(1, env)?;
+    let user_portfolio = competition.get_user_portfolio(user_id, env)?;
+    let rival_portfolio = competition.get_user_portfolio(rival_id.clone(), env)?;
+    let remaining_after_second_batch = competition.burn_virtual_assets(10, env)?;
+
+    // Assert
+    assert_eq!(remaining_after_first_batch, 1);
+    assert_eq!(user_portfolio.total_value, Decimal::zero());
+    assert_eq!(rival_portfolio.total_value, dec!(10000));
+    assert_eq!(remaining_after_second_batch, 0);
+    assert_eq!(
+        competition.get_user_portfolio(rival_id, env)?.total_value,
+        Decimal::zero()
+    );
+    assert_eq!(
+        competition.get_leaderboard(env)?[0].portfolio_value,
+        dec!(10000)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cannot_burn_virtual_assets_before_settlement() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        mut competition,
+        user_token_proof,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    competition.register(user_token_proof, None, None, env)?;
+
+    let competition_end_time = competition.get_competition_end_time(env)?;
+    env.set_current_time(competition_end_time);
+
+    // Act
+    let result = competition.burn_virtual_assets(10, env);
+
+    // Assert
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_virtual_assets_cannot_be_deposited_into_accounts() -> Result<(), RuntimeError> {
+    // Arrange
+    let UnitTestEnvironment {
+        ref mut env,
+        resource_addresses,
+        ..
+    } = UnitTestEnvironment::new()?;
+
+    // Minting outside the competition only works because auth is still disabled here.
+    let fusd = ResourceManager(resource_addresses.fusd).mint_fungible(dec!(100), env)?;
+    let other_tokens = UnitTestEnvironment::create_entry_fee_tokens(env)?;
+    let (account, _) = env.call_function_typed::<_, AccountCreateOutput>(
+        ACCOUNT_PACKAGE,
+        ACCOUNT_BLUEPRINT,
+        ACCOUNT_CREATE_IDENT,
+        &AccountCreateInput {},
+    )?;
+    env.enable_auth_module();
+
+    // Act
+    let other_tokens_result = env.call_method_typed::<_, _, ()>(
+        account.0,
+        ACCOUNT_TRY_DEPOSIT_OR_ABORT_IDENT,
+        &AccountTryDepositOrAbortInput {
+            bucket: other_tokens.into(),
+            authorized_depositor_badge: None,
+        },
+    );
+    let fusd_result = env.call_method_typed::<_, _, ()>(
+        account.0,
+        ACCOUNT_TRY_DEPOSIT_OR_ABORT_IDENT,
+        &AccountTryDepositOrAbortInput {
+            bucket: fusd.into(),
+            authorized_depositor_badge: None,
+        },
+    );
+
+    // Assert
+    assert!(other_tokens_result.is_ok());
+    assert!(fusd_result.is_err());
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use scrypto_test::prelude::*;
+
+use the_trenches::vesting::{PrizeClaim, VestingSchedule};
+
+const THIRTY_DAYS: i64 = 30 * 24 * 60 * 60;
+
+fn schedule() -> VestingSchedule {
+    VestingSchedule {
+        cliff_share: dec!("0.2"),
+        duration: THIRTY_DAYS,
+    }
+}
+
+#[test]
+fn test_cliff_unlocks_at_start_and_rest_linearly() {
+    let start = Instant::new(1000);
+
+    assert_eq!(
+        schedule().unlocked_amount(dec!(100), start, Instant::new(999)),
+        Decimal::zero()
+    );
+    assert_eq!(
+        schedule().unlocked_amount(dec!(100), start, start),
+        dec!(20)
+    );
+    assert_eq!(
+        schedule().unlocked_amount(dec!(100), start, start.add_days(15).unwrap()),
+        dec!(60)
+    );
+    assert_eq!(
+        schedule().unlocked_amount(dec!(100), start, start.add_days(45).unwrap()),
+        dec!(100)
+    );
+}
+
+#[test]
+fn test_claimable_amount_excludes_claimed_prize() {
+    let start = Instant::new(0);
+    let prize_claim = PrizeClaim {
+        resource_address: XRD,
+        total_amount: dec!(100),
+        claimed_amount: dec!(20),
+        vesting_start: start,
+        schedule: schedule(),
+    };
+
+    assert_eq!(prize_claim.claimable_amount(start, 18), Decimal::zero());
+    assert_eq!(
+        prize_claim.claimable_amount(start.add_days(15).unwrap(), 18),
+        dec!(40)
+    );
+}